@@ -0,0 +1,129 @@
+//! Repositories and sources built on the fly for the tests.
+
+/// Creates an empty repository in a fresh temporary directory, which is
+/// removed once the returned guard is dropped.
+pub fn Init() -> (TempDir, Repository) {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	let Repository = Repository::init(Directory.path()).expect("Cannot init.");
+
+	(Directory, Repository)
+}
+
+/// A signature for `Name` at `Seconds` past the epoch, in UTC.
+pub fn Sign(Name:&str, Seconds:i64) -> Signature<'static> {
+	Signature::new(Name, &format!("{}@example.com", Name.to_lowercase()), &Time::new(Seconds, 0))
+		.expect("Cannot Signature.")
+}
+
+/// Writes `File` into the work tree, stages every change and commits it on
+/// `HEAD` as `Author`, who also commits it.
+pub fn Commit(
+	Repository:&Repository,
+	File:&[(&str, &str)],
+	Message:&str,
+	Author:&Signature,
+) -> Oid {
+	CommitAs(Repository, File, Message, Author, Author)
+}
+
+/// `Commit`, with `Committer` recorded apart from `Author`.
+pub fn CommitAs(
+	Repository:&Repository,
+	File:&[(&str, &str)],
+	Message:&str,
+	Author:&Signature,
+	Committer:&Signature,
+) -> Oid {
+	let Parent = Repository.head().ok().and_then(|Head| Head.peel_to_commit().ok());
+
+	Record(Repository, File, Message, Author, Committer, Parent.iter().collect())
+}
+
+/// `Commit`, recording `Other` as a second parent so the commit is a merge.
+pub fn Merge(
+	Repository:&Repository,
+	File:&[(&str, &str)],
+	Message:&str,
+	Author:&Signature,
+	Other:Oid,
+) -> Oid {
+	let Head = Repository.head().and_then(|Head| Head.peel_to_commit()).expect("Cannot HEAD.");
+
+	let Other = Repository.find_commit(Other).expect("Cannot find_commit.");
+
+	Record(Repository, File, Message, Author, Author, vec![&Head, &Other])
+}
+
+fn Record(
+	Repository:&Repository,
+	File:&[(&str, &str)],
+	Message:&str,
+	Author:&Signature,
+	Committer:&Signature,
+	Parent:Vec<&git2::Commit>,
+) -> Oid {
+	let Work = Repository.workdir().expect("Cannot workdir.");
+
+	for (Path, Content) in File {
+		let Path = Work.join(Path);
+
+		if let Some(Parent) = Path.parent() {
+			std::fs::create_dir_all(Parent).expect("Cannot create_dir_all.");
+		}
+
+		std::fs::write(Path, Content).expect("Cannot write.");
+	}
+
+	let mut Index = Repository.index().expect("Cannot index.");
+
+	Index.add_all(["*"], IndexAddOption::DEFAULT, None).expect("Cannot add_all.");
+
+	Index.update_all(["*"], None).expect("Cannot update_all.");
+
+	Index.write().expect("Cannot write index.");
+
+	let Tree = Repository
+		.find_tree(Index.write_tree().expect("Cannot write_tree."))
+		.expect("Cannot find_tree.");
+
+	Repository
+		.commit(Some("HEAD"), Author, Committer, Message, &Tree, &Parent)
+		.expect("Cannot commit.")
+}
+
+/// An `Option` compiling the TypeScript under `Directory` as the command line
+/// would, quietly and with the cache kept in `Directory`.
+pub fn Project(Directory:&Path) -> Option {
+	Option {
+		entry:vec![vec![Directory.to_string_lossy().to_string()]],
+		patterns:vec![".ts".to_string(), ".tsx".to_string()],
+		config:CompilerConfig::default(),
+		config_path:None,
+		force:false,
+		debounce_ms:150,
+		ignore:vec!["node_modules".to_string(), ".git".to_string()],
+		concurrency:2,
+		batch_size:1,
+		report_path:None,
+		dry_run:false,
+		on_compiled:None,
+		quiet:true,
+		retry_reads:false,
+		exclude_tests:false,
+		test_patterns:Option::TEST_PATTERNS.map(str::to_string).to_vec(),
+		metrics:None,
+		metrics_flush_secs:0,
+		fail_fast:false,
+		per_file_timeout_secs:0,
+		bundle:None,
+		cache_path:Directory.join(Cache::PATH),
+	}
+}
+
+use std::path::Path;
+
+use git2::{IndexAddOption, Oid, Repository, Signature, Time};
+use tempfile::TempDir;
+
+use crate::Struct::SWC::{Cache, CompilerConfig, Option};
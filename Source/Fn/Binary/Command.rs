@@ -19,15 +19,35 @@
 ///   ".git".
 /// * `Root` - An optional argument to specify the root directory. Default is
 ///   ".".
+/// * `Concurrency` - An optional argument to cap how many entries are processed
+///   at once in parallel. Default is the number of CPUs.
+/// * `Changelog` - An optional argument to write a Markdown changelog of the
+///   collected commits to the given path.
+/// * `Url` - An optional argument to link the commit ids in the changelog,
+///   appending each id to the given prefix.
+/// * `Stat` - An optional flag to collect change stats instead of full patches.
+/// * `Dedup` - An optional flag to collapse commits sharing a summary into the
+///   most recent one, suffixed with their count.
+/// * `Format` - An optional argument to choose between the grouped `text`
+///   report and `jsonl`, one JSON object per commit. Default is "text".
+/// * `Leaderboard` - An optional flag to print the commits, insertions and
+///   deletions of every author, ranked by insertions.
+/// * `SinceTag` - An optional flag to collect only the commits made since the
+///   most recent tag.
+/// * `Truncate` - An optional argument to cut summaries in the report to the
+///   given number of characters.
+/// * `Body` - An optional flag to print the full commit message under each
+///   summary in the report.
 ///
 /// # Example
 ///
-/// ```rust
+/// ```rust,no_run
+/// # use Library::Fn::Binary::Command::Fn;
 /// let matches = Fn();
-/// let exclude = matches.value_of("Exclude").unwrap_or("node_modules");
-/// let parallel = matches.is_present("Parallel");
-/// let pattern = matches.value_of("Pattern").unwrap_or(".git");
-/// let root = matches.value_of("Root").unwrap_or(".");
+/// let exclude = matches.get_one::<String>("Exclude").map_or("node_modules", String::as_str);
+/// let parallel = matches.get_flag("Parallel");
+/// let pattern = matches.get_one::<String>("Pattern").map_or(".git", String::as_str);
+/// let root = matches.get_one::<String>("Root").map_or(".", String::as_str);
 /// ```
 ///
 /// # Errors
@@ -78,11 +98,103 @@ pub fn Fn() -> ArgMatches {
 				.help("📂 Root —")
 				.default_value("."),
 		)
+		.arg(
+			Arg::new("Concurrency")
+				.short('C')
+				.long("Concurrency")
+				.display_order(6)
+				.value_name("CONCURRENCY")
+				.required(false)
+				.value_parser(value_parser!(usize))
+				.help("🔢 Concurrency —"),
+		)
+		.arg(
+			Arg::new("Changelog")
+				.long("Changelog")
+				.display_order(7)
+				.value_name("CHANGELOG")
+				.required(false)
+				.help("📝 Changelog —"),
+		)
+		.arg(
+			Arg::new("Url")
+				.long("Url")
+				.display_order(8)
+				.value_name("URL")
+				.required(false)
+				.requires("Changelog")
+				.help("🔗 Url —"),
+		)
+		.arg(
+			Arg::new("Stat")
+				.long("Stat")
+				.action(SetTrue)
+				.display_order(9)
+				.value_name("STAT")
+				.required(false)
+				.help("📊 Stat —"),
+		)
+		.arg(
+			Arg::new("Dedup")
+				.long("Dedup")
+				.action(SetTrue)
+				.display_order(10)
+				.value_name("DEDUP")
+				.required(false)
+				.help("🧹 Dedup —"),
+		)
+		.arg(
+			Arg::new("Format")
+				.long("Format")
+				.display_order(11)
+				.value_name("FORMAT")
+				.required(false)
+				.value_parser(["text", "jsonl"])
+				.help("🧾 Format —")
+				.default_value("text"),
+		)
+		.arg(
+			Arg::new("Leaderboard")
+				.long("Leaderboard")
+				.action(SetTrue)
+				.display_order(12)
+				.value_name("LEADERBOARD")
+				.required(false)
+				.help("🏆 Leaderboard —"),
+		)
+		.arg(
+			Arg::new("SinceTag")
+				.long("SinceTag")
+				.action(SetTrue)
+				.display_order(13)
+				.value_name("SINCETAG")
+				.required(false)
+				.help("🏷️ SinceTag —"),
+		)
+		.arg(
+			Arg::new("Truncate")
+				.long("Truncate")
+				.display_order(14)
+				.value_name("TRUNCATE")
+				.required(false)
+				.value_parser(clap::value_parser!(usize))
+				.help("✂️ Truncate —"),
+		)
+		.arg(
+			Arg::new("Body")
+				.long("Body")
+				.action(SetTrue)
+				.display_order(15)
+				.value_name("BODY")
+				.required(false)
+				.help("📄 Body —"),
+		)
 		.get_matches()
 }
 
-use clap::{Arg, ArgAction::SetTrue, ArgMatches, Command};
+use clap::{value_parser, Arg, ArgAction::SetTrue, ArgMatches, Command};
 
 pub mod Entry;
 pub mod Parallel;
+pub mod Report;
 pub mod Sequential;
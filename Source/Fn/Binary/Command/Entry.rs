@@ -7,12 +7,12 @@
 ///   fields:
 ///   - `Exclude`: A vector of strings representing patterns to exclude.
 ///   - `Root`: The root directory to start the walk from.
-///   - `Separator`: The separator used for splitting file paths.
 ///
 /// # Returns
 ///
 /// Returns a vector of vectors, where each inner vector contains the components
-/// of a file path split by the specified separator.
+/// of a file path, so that collecting them into a `PathBuf` reconstructs it on
+/// any platform.
 ///
 /// # Panics
 ///
@@ -21,18 +21,32 @@
 ///
 /// # Example
 ///
-/// ```
+/// ```no_run
+/// # use Library::Fn::Binary::Command::Entry::Fn;
+/// # use Library::Struct::Binary::Command::Option::Struct as Option;
 /// let options = Option {
 /// 	Exclude:vec!["node_modules".to_string(), "target".to_string()],
+/// 	Parallel:false,
+/// 	Pattern:".git".to_string(),
 /// 	Root:".".to_string(),
-/// 	Separator:'/',
+/// 	Separator:std::path::MAIN_SEPARATOR,
+/// 	Concurrency:4,
+/// 	Changelog:None,
+/// 	Url:None,
+/// 	Stat:false,
+/// 	Dedup:false,
+/// 	Format:"text".to_string(),
+/// 	Leaderboard:false,
+/// 	SinceTag:false,
+/// 	Truncate:None,
+/// 	Body:false,
 /// };
 /// let paths = Fn(&options);
 /// for path in paths {
 /// 	println!("{:?}", path);
 /// }
 /// ```
-pub fn Fn(Option { Exclude, Root, Pattern, Separator, .. }:&Option) -> Return {
+pub fn Fn(Option { Exclude, Root, Pattern, .. }:&Option) -> Return {
 	WalkDir::new(Root)
 		.follow_links(true)
 		.into_iter()
@@ -46,7 +60,12 @@ pub fn Fn(Option { Exclude, Root, Pattern, Separator, .. }:&Option) -> Return {
 				.filter(|Exclude| *Pattern != *Exclude)
 				.any(|Exclude| Path.contains(&Exclude))
 			{
-				Some(Path.split(*Separator).map(|Entry| Entry.to_string()).collect())
+				Some(
+					std::path::Path::new(&Path)
+						.components()
+						.map(|Entry| Entry.as_os_str().to_string_lossy().to_string())
+						.collect(),
+				)
 			} else {
 				None
 			}
@@ -57,3 +76,53 @@ pub fn Fn(Option { Exclude, Root, Pattern, Separator, .. }:&Option) -> Return {
 use walkdir::WalkDir;
 
 use crate::Struct::Binary::Command::{Entry::Type as Return, Option::Struct as Option};
+
+#[cfg(test)]
+mod Test {
+	#[test]
+	fn ComponentsRebuildEachPath() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		// A backslash is an ordinary character of a POSIX name, not a separator.
+		let Nested = match cfg!(windows) {
+			true => Directory.path().join("a").join("b"),
+			false => Directory.path().join("a").join("b\\c"),
+		};
+
+		std::fs::create_dir_all(Nested.join(".git")).unwrap();
+
+		let Entry = Fn(&Option {
+			Exclude:Vec::new(),
+			Parallel:false,
+			Pattern:".git".to_string(),
+			Root:Directory.path().to_string_lossy().to_string(),
+			Separator:std::path::MAIN_SEPARATOR,
+			Concurrency:1,
+			Changelog:None,
+			Url:None,
+			Stat:false,
+			Dedup:false,
+			Format:"text".to_string(),
+			Leaderboard:false,
+			SinceTag:false,
+			Truncate:None,
+			Body:false,
+		});
+
+		let Path = Entry.iter().map(|Entry| Entry.iter().collect::<PathBuf>()).collect::<Vec<_>>();
+
+		assert_eq!(Path.len(), 4);
+
+		assert!(Path.contains(&Nested.join(".git")));
+
+		let Repository = Entry.iter().find(|Entry| Entry.last().is_some_and(|Last| Last == ".git"));
+
+		let Repository = Repository.unwrap();
+
+		assert_eq!(Repository[..Repository.len() - 1].iter().collect::<PathBuf>(), Nested);
+	}
+
+	use std::path::PathBuf;
+
+	use super::*;
+}
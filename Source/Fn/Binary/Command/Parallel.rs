@@ -2,54 +2,101 @@
 /// results.
 ///
 /// This function performs the following steps:
-/// 1. Filters and processes the provided entries based on the given pattern and
-///    separator.
-/// 2. Spawns asynchronous tasks to generate summaries for each entry.
-/// 3. Collects the results and outputs them.
+/// 1. Filters the provided entries on the given pattern and joins the components
+///    of each into a path.
+/// 2. Spawns asynchronous tasks to generate summaries for each entry, with at
+///    most `Concurrency` of them building at the same time.
+/// 3. Collects the results as they complete and outputs them, streaming each
+///    repository as JSON lines the moment it completes when `Format` is
+///    `jsonl`, and writing a changelog of them when `Changelog` is set.
 ///
 /// # Arguments
 ///
 /// * `Option` - A struct containing the following fields:
 ///   - `Entry`: A vector of vectors, where each inner vector contains the
 ///     components of a file path.
-///   - `Separator`: A character used to join the components of the file path.
 ///   - `Pattern`: A string pattern to match against the last element of each
 ///     entry.
+///   - `Concurrency`: The maximum number of summaries built at once.
+///   - `Changelog`: An optional path a Markdown changelog is written to.
+///   - `Url`: An optional prefix the commit ids in the changelog link to.
+///   - `Stat`: Whether to collect change stats instead of full patches.
+///   - `Dedup`: Whether to collapse commits sharing a summary.
+///   - `Format`: `text` for the grouped report or `jsonl` for one JSON object
+///     per commit.
+///   - `Leaderboard`: Whether to print the commits and lines of every author.
+///   - `SinceTag`: Whether to collect only the commits since the last tag.
+///   - `Truncate`: An optional number of characters summaries are cut to.
+///   - `Body`: Whether to print the full commit message under each summary.
+///   - `Print`: Whether to print the report, the JSON lines and the
+///     leaderboard, which are otherwise left to the caller.
 ///
 /// # Example
 ///
-/// ```rust
+/// ```rust,no_run
+/// # use Library::Fn::Binary::Command::Parallel::Fn;
+/// # use Library::Struct::Binary::Command::Entry::Struct as Option;
+/// # async fn Run() {
 /// let options = Option {
 /// 	Entry:vec![vec!["path".to_string(), "to".to_string(), "file.git".to_string()]],
-/// 	Separator:'/',
+/// 	Parallel:true,
 /// 	Pattern:".git".to_string(),
+/// 	Separator:std::path::MAIN_SEPARATOR,
+/// 	Concurrency:4,
+/// 	Changelog:Some("CHANGELOG.md".to_string()),
+/// 	Url:None,
+/// 	Stat:false,
+/// 	Dedup:false,
+/// 	Format:"text".to_string(),
+/// 	Leaderboard:false,
+/// 	SinceTag:false,
+/// 	Truncate:Some(72),
+/// 	Body:false,
+/// 	Print:true,
 /// };
-/// Fn(options).await;
+/// let results = Fn(options).await;
+/// # }
 /// ```
 ///
+/// # Returns
+///
+/// Returns each repository path paired with the commits collected from it, in
+/// the order they completed.
+///
 /// # Errors
 ///
 /// This function will log errors if it fails to generate summaries or send
 /// results.
-pub async fn Fn(Option { Entry, Separator, Pattern, .. }:Option) {
+pub async fn Fn(Option:Option) -> Vec<(String, Type)> {
+	let Option { ref Entry, ref Pattern, Concurrency, Stat, Dedup, SinceTag, .. } = Option;
+
 	let (Allow, mut Mark) = tokio::sync::mpsc::unbounded_channel();
 	let Queue = futures::stream::FuturesUnordered::new();
+	let Permit = Arc::new(Semaphore::new(Concurrency.max(1)));
+	let Cache = Arc::new(Cache::default());
 
 	for Entry in Entry
 		.into_par_iter()
 		.filter_map(|Entry| {
-			if globset::GlobSet.is_match(&Entry.join(&Separator.to_string())) {
-				Some(Entry[0..Entry.len() - 1].join(&Separator.to_string()))
-			} else {
-				None
-			}
+			Entry
+				.last()
+				.filter(|Last| *Last == Pattern)
+				.map(|_| {
+					Entry[0..Entry.len() - 1].iter().collect::<PathBuf>().display().to_string()
+				})
 		})
 		.collect::<Vec<String>>()
 	{
 		let Allow = Allow.clone();
+		let Permit = Arc::clone(&Permit);
+		let Cache = Arc::clone(&Cache);
 
 		Queue.push(tokio::spawn(async move {
-			match crate::Fn::Build::Fn(&Entry).await {
+			let _Permit = Permit.acquire_owned().await.expect("Cannot Permit.");
+
+			let Option = Build { Stat, Dedup, SinceTag, Cache:Some(Cache), ..Default::default() };
+
+			match crate::Fn::Build::Fn(&Entry, &Option).await {
 				Ok(Build) => {
 					if let Err(_Error) = Allow.send((Entry, Build)) {
 						eprintln!("Cannot Allow: {}", _Error);
@@ -71,13 +118,94 @@ pub async fn Fn(Option { Entry, Separator, Pattern, .. }:Option) {
 	let mut Output = Vec::new();
 
 	while let Some((Entry, Build)) = Mark.recv().await {
+		Report::Stream(&Entry, &Build, &Option);
+
 		Output.push((Entry, Build));
 	}
 
-	crate::Fn::Build::Group::Fn(Output);
+	Report::Fn(&Output, &Option);
+
+	Output
 }
 
+use std::{path::PathBuf, sync::Arc};
+
 use futures::stream::StreamExt;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use tokio::sync::Semaphore;
+
+use crate::{
+	Fn::Binary::Command::Report,
+	Struct::{
+		Binary::Command::Entry::Struct as Option,
+		Build::{Cache, Struct as Build, Type},
+	},
+};
+
+#[cfg(test)]
+mod Test {
+	#[tokio::test(flavor = "multi_thread")]
+	async fn CollectsEveryRepository() {
+		let Repository = (0..3).map(|_| Fixture::Init()).collect::<Vec<_>>();
 
-use crate::Struct::Binary::Command::Entry::Struct as Option;
+		for (Index, (_, Repository)) in Repository.iter().enumerate() {
+			for Commit in 0..=Index {
+				Fixture::Commit(
+					Repository,
+					&[("a", &Commit.to_string())],
+					&format!("feat: {} {}", Index, Commit),
+					&Fixture::Sign("Ann", 1 + Commit as i64),
+				);
+			}
+		}
+
+		let Entry = Repository
+			.iter()
+			.map(|(Directory, _)| {
+				Directory
+					.path()
+					.components()
+					.map(|Component| Component.as_os_str().to_string_lossy().into_owned())
+					.chain([".git".to_string()])
+					.collect()
+			})
+			.collect();
+
+		let mut Output = Fn(Option {
+			Entry,
+			Parallel:true,
+			Pattern:".git".to_string(),
+			Separator:std::path::MAIN_SEPARATOR,
+			Concurrency:2,
+			Changelog:None,
+			Url:None,
+			Stat:true,
+			Dedup:false,
+			Format:"text".to_string(),
+			Leaderboard:false,
+			SinceTag:false,
+			Truncate:None,
+			Body:false,
+			Print:false,
+		})
+		.await
+		.into_iter()
+		.map(|(Entry, Build)| (PathBuf::from(Entry), Build.len()))
+		.collect::<Vec<_>>();
+
+		Output.sort();
+
+		let mut Expected = Repository
+			.iter()
+			.enumerate()
+			.map(|(Index, (Directory, _))| (Directory.path().to_path_buf(), Index + 1))
+			.collect::<Vec<_>>();
+
+		Expected.sort();
+
+		assert_eq!(Output, Expected);
+	}
+
+	use super::*;
+	use crate::Fixture;
+}
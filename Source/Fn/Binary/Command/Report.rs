@@ -0,0 +1,96 @@
+/// Writes what `Sequential::Fn` and `Parallel::Fn` collected: the changelog
+/// when `Changelog` is set and, with `Print`, the leaderboard and the grouped
+/// report unless `Format` is `jsonl`, whose lines `Stream` has already written.
+///
+/// # Arguments
+///
+/// * `Output` - Each repository path paired with the commits collected from it.
+/// * `Option` - The options the collecting function was called with.
+pub fn Fn(
+	Output:&[(String, Type)],
+	Option { Changelog, Url, Format, Leaderboard, Truncate, Body, Print, .. }:&Option,
+) {
+	if let Some(Changelog) = Changelog {
+		let Markdown = crate::Fn::Build::Group::Markdown(
+			&crate::Fn::Build::Group::Changelog(Output.to_vec()),
+			Url.as_deref(),
+		);
+
+		if let Err(_Error) = std::fs::write(Changelog, Markdown) {
+			eprintln!("Cannot Changelog {}: {}", Changelog, _Error);
+		}
+	}
+
+	if *Print && *Leaderboard {
+		print!(
+			"{}",
+			crate::Fn::Build::Group::Leaderboard(&crate::Fn::Build::Group::Contribution(
+				Output.to_vec()
+			))
+		);
+	}
+
+	if *Print && Format != "jsonl" {
+		crate::Fn::Build::Group::Print(&crate::Fn::Build::Group::Fn(
+			Output.to_vec(),
+			*Truncate,
+			*Body,
+		));
+	}
+}
+
+/// Writes the commits of `Entry` as JSON lines when `Print` is set and
+/// `Format` is `jsonl`, reporting rather than returning a failure to write.
+pub fn Stream(Entry:&str, Build:&Type, Option { Format, Print, .. }:&Option) {
+	if *Print && Format == "jsonl" {
+		if let Err(_Error) = crate::Fn::Build::Stream::Fn(Entry, Build) {
+			eprintln!("Cannot Stream for {}: {}", Entry, _Error);
+		}
+	}
+}
+
+use crate::Struct::{Binary::Command::Entry::Struct as Option, Build::Type};
+
+#[cfg(test)]
+mod Test {
+	#[tokio::test]
+	async fn ChangelogIsWrittenUnprinted() {
+		let (Directory, Repository) = Fixture::Init();
+
+		Fixture::Commit(&Repository, &[("a", "a")], "feat: a", &Fixture::Sign("Ann", 1));
+
+		let Entry = Directory.path().to_string_lossy().to_string();
+
+		let Build = crate::Fn::Build::Fn(&Entry, &Default::default()).await.unwrap();
+
+		let Changelog = Directory.path().join("CHANGELOG.md");
+
+		Fn(
+			&[(Entry, Build)],
+			&Option {
+				Entry:Vec::new(),
+				Parallel:false,
+				Pattern:".git".to_string(),
+				Separator:std::path::MAIN_SEPARATOR,
+				Concurrency:1,
+				Changelog:Some(Changelog.to_string_lossy().to_string()),
+				Url:None,
+				Stat:false,
+				Dedup:false,
+				Format:"text".to_string(),
+				Leaderboard:false,
+				SinceTag:false,
+				Truncate:None,
+				Body:false,
+				Print:false,
+			},
+		);
+
+		let Markdown = std::fs::read_to_string(Changelog).unwrap();
+
+		assert!(Markdown.contains("## Features\n\n- ["), "{}", Markdown);
+	}
+
+	use super::*;
+	use crate::Fixture;
+}
@@ -2,48 +2,92 @@
 /// results sequentially.
 ///
 /// This function performs the following steps:
-/// 1. Filters and processes the provided entries based on the given pattern and
-///    separator.
+/// 1. Filters the provided entries on the given pattern and joins the components
+///    of each into a path.
 /// 2. Spawns asynchronous tasks to generate summaries for each entry.
-/// 3. Collects the results and outputs them.
+/// 3. Collects the results and outputs them, as JSON lines when `Format` is
+///    `jsonl`, writing a changelog of them when `Changelog` is set.
 ///
 /// # Arguments
 ///
 /// * `Option` - A struct containing the following fields:
 ///   - `Entry`: A vector of vectors, where each inner vector contains the
 ///     components of a file path.
-///   - `Separator`: A character used to join the components of the file path.
 ///   - `Pattern`: A string pattern to match against the last element of each
 ///     entry.
+///   - `Changelog`: An optional path a Markdown changelog is written to.
+///   - `Url`: An optional prefix the commit ids in the changelog link to.
+///   - `Stat`: Whether to collect change stats instead of full patches.
+///   - `Dedup`: Whether to collapse commits sharing a summary.
+///   - `Format`: `text` for the grouped report or `jsonl` for one JSON object
+///     per commit.
+///   - `Leaderboard`: Whether to print the commits and lines of every author.
+///   - `SinceTag`: Whether to collect only the commits since the last tag.
+///   - `Truncate`: An optional number of characters summaries are cut to.
+///   - `Body`: Whether to print the full commit message under each summary.
+///   - `Print`: Whether to print the report, the JSON lines and the
+///     leaderboard, which are otherwise left to the caller.
 ///
 /// # Example
 ///
-/// ```rust
+/// ```rust,no_run
+/// # use Library::Fn::Binary::Command::Sequential::Fn;
+/// # use Library::Struct::Binary::Command::Entry::Struct as Option;
+/// # async fn Run() {
 /// let options = Option {
 /// 	Entry:vec![vec!["path".to_string(), "to".to_string(), "file.git".to_string()]],
-/// 	Separator:'/',
+/// 	Parallel:false,
 /// 	Pattern:".git".to_string(),
+/// 	Separator:std::path::MAIN_SEPARATOR,
+/// 	Concurrency:1,
+/// 	Changelog:Some("CHANGELOG.md".to_string()),
+/// 	Url:None,
+/// 	Stat:false,
+/// 	Dedup:false,
+/// 	Format:"text".to_string(),
+/// 	Leaderboard:false,
+/// 	SinceTag:false,
+/// 	Truncate:Some(72),
+/// 	Body:false,
+/// 	Print:true,
 /// };
-/// Fn(options).await;
+/// let results = Fn(options).await;
+/// # }
 /// ```
 ///
+/// # Returns
+///
+/// Returns each repository path paired with the commits collected from it, in
+/// the order the entries were given.
+///
 /// # Errors
 ///
 /// This function will log errors if it fails to generate summaries or send
 /// results.
-pub async fn Fn(Option { Entry, Pattern, Separator, .. }:Option) {
+pub async fn Fn(Option:Option) -> Vec<(String, Type)> {
+	let Option { ref Entry, ref Pattern, Stat, Dedup, SinceTag, .. } = Option;
+
+	let Cache = Arc::new(Cache::default());
+
 	let Queue = futures::future::join_all(
 		Entry
-			.into_iter()
+			.iter()
 			.filter_map(|Entry| {
 				Entry
 					.last()
-					.filter(|Last| *Last == &Pattern)
-					.map(|_| Entry[0..Entry.len() - 1].join(&Separator.to_string()))
+					.filter(|Last| *Last == Pattern)
+					.map(|_| {
+						Entry[0..Entry.len() - 1].iter().collect::<PathBuf>().display().to_string()
+					})
 			})
 			.map(|Entry| {
+				let Cache = Arc::clone(&Cache);
+
 				async move {
-					match crate::Fn::Build::Fn(&Entry).await {
+					let Option =
+						Build { Stat, Dedup, SinceTag, Cache:Some(Cache), ..Default::default() };
+
+					match crate::Fn::Build::Fn(&Entry, &Option).await {
 						Ok(Build) => Ok((Entry, Build)),
 						Err(_Error) => {
 							Err(format!("Error generating summary for {}: {}", Entry, _Error))
@@ -54,7 +98,82 @@ pub async fn Fn(Option { Entry, Pattern, Separator, .. }:Option) {
 	)
 	.await;
 
-	crate::Fn::Build::Group::Fn(Queue.into_iter().filter_map(Result::ok).collect::<Vec<_>>());
+	let Output = Queue.into_iter().filter_map(Result::ok).collect::<Vec<_>>();
+
+	for (Entry, Build) in &Output {
+		Report::Stream(Entry, Build, &Option);
+	}
+
+	Report::Fn(&Output, &Option);
+
+	Output
 }
 
-use crate::Struct::Binary::Command::Entry::Struct as Option;
+use std::{path::PathBuf, sync::Arc};
+
+use crate::{
+	Fn::Binary::Command::Report,
+	Struct::{
+		Binary::Command::Entry::Struct as Option,
+		Build::{Cache, Struct as Build, Type},
+	},
+};
+
+#[cfg(test)]
+mod Test {
+	#[tokio::test]
+	async fn ReturnsCommitsUnprinted() {
+		let (Directory, Repository) = Fixture::Init();
+
+		Fixture::Commit(&Repository, &[("a", "a")], "feat: a", &Fixture::Sign("Ann", 1));
+
+		Fixture::Commit(&Repository, &[("b", "b")], "fix: b", &Fixture::Sign("Bob", 2));
+
+		let Entry = Directory
+			.path()
+			.components()
+			.map(|Component| Component.as_os_str().to_string_lossy().into_owned())
+			.chain([".git".to_string()])
+			.collect();
+
+		let Output = Fn(Option {
+			Entry:vec![Entry],
+			Parallel:false,
+			Pattern:".git".to_string(),
+			Separator:std::path::MAIN_SEPARATOR,
+			Concurrency:1,
+			Changelog:None,
+			Url:None,
+			Stat:true,
+			Dedup:false,
+			Format:"text".to_string(),
+			Leaderboard:false,
+			SinceTag:false,
+			Truncate:None,
+			Body:false,
+			Print:false,
+		})
+		.await;
+
+		assert_eq!(Output.len(), 1);
+
+		assert_eq!(PathBuf::from(&Output[0].0), Directory.path());
+
+		let Summary = |Key:u64| {
+			Output[0].1.get(&Key).map(|Entry| Entry.1.lines().next().unwrap().to_string())
+		};
+
+		assert_eq!(Summary(0).as_deref(), Some("fix: b"));
+
+		assert_eq!(Summary(1).as_deref(), Some("feat: a"));
+
+		let Group = crate::Fn::Build::Group::Fn(Output, None, false);
+
+		assert_eq!(Group.get("Ann <ann>"), Some(&vec!["feat: a".to_string()]));
+
+		assert_eq!(Group.get("Bob <bob>"), Some(&vec!["fix: b".to_string()]));
+	}
+
+	use super::*;
+	use crate::Fixture;
+}
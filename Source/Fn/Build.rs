@@ -1,5 +1,718 @@
-pub async fn Fn(Entry:&str) -> Result<DashMap<u64, (String, String)>, Box<dyn std::error::Error>> {
+/// Walks the history of the repository at `Entry`, starting from `HEAD` or from
+/// `Option.Rev` when it is set, and stopping at the last tag before it when
+/// `Option.SinceTag` is set.
+///
+/// Commits are selected in walk order and then diffed in parallel, each rayon
+/// worker opening its own handle on the repository. Merge commits are skipped
+/// unless `Option.Merge` says otherwise. git2 and rayon block, so all of it
+/// runs on the blocking pool instead of the runtime workers.
+///
+/// # Arguments
+///
+/// * `Entry` - The path of the repository, or any path inside it.
+/// * `Option` - The options restricting which commits are collected.
+///
+/// # Returns
+///
+/// Returns a map from the key chosen by `Option.Key` to the id of each commit
+/// and its summary followed by a blank line and the patch it introduced, or
+/// its stats with `Option.Stat`. With
+/// `Key::Index` the key is the sequential walk index and the id is short, so
+/// the walk order can be recovered by sorting on the key; with `Key::Oid` the
+/// key is derived from the commit itself and the id is the full sha. Either
+/// way the summary is the first line.
+///
+/// # Errors
+///
+/// This function will return `Error::NotARepository` if `Entry` is not inside a
+/// repository, `Error::Rev` if `Option.Rev` does not resolve to a commit, and
+/// `Error::Git` if the repository or any commit in the walk cannot be read,
+/// `Error::Pool` if `Option.Threads` is set and its pool cannot start, and
+/// `Error::Task` if the blocking task running the walk panics.
+pub async fn Fn(
+	Entry:&str,
+	Option:&Option,
+) -> Result<Type, Error> {
+	let (Entry, Option) = (Entry.to_string(), Option.clone());
+
+	tokio::task::spawn_blocking(move || Walk(&Entry, &Option))
+		.await
+		.map_err(|_Error| Error::Task(_Error.to_string()))?
+}
+
+/// Does the work of `Fn` on the calling thread.
+fn Walk(Entry:&str, Option:&Option) -> Result<Type, Error> {
 	let Build = DashMap::new();
 
+	let Repository = match &Option.Cache {
+		Some(Cache) => {
+			let Root = match Cache.Root.get(Path::new(Entry)) {
+				Some(Root) => {
+					Cache.Hit.fetch_add(1, Ordering::Relaxed);
+
+					Root.clone()
+				},
+				None => {
+					let Root = Repository::discover(Entry)
+						.map_err(|_Error| Error::open(Path::new(Entry), _Error))?
+						.path()
+						.to_path_buf();
+
+					Cache.Root.insert(PathBuf::from(Entry), Root.clone());
+
+					Root
+				},
+			};
+
+			Repository::open(&Root).map_err(|_Error| Error::open(&Root, _Error))?
+		},
+		None => {
+			Repository::discover(Entry).map_err(|_Error| Error::open(Path::new(Entry), _Error))?
+		},
+	};
+
+	let mut Walk = Repository.revwalk()?;
+
+	Walk.set_sorting(Sort::TIME)?;
+
+	let Start = match &Option.Rev {
+		Some(Rev) => {
+			Repository
+				.revparse_single(Rev)
+				.and_then(|Object| Object.peel_to_commit())
+				.map_err(|_Error| Error::Rev(Rev.clone(), _Error))?
+				.id()
+		},
+		None => Repository.head()?.peel_to_commit()?.id(),
+	};
+
+	Walk.push(Start)?;
+
+	if Option.SinceTag {
+		if let Some(Tag) = Tag(&Repository, Start)? {
+			Walk.hide(Tag)?;
+		}
+	}
+
+	// Summary of each representative commit, with its key and how many
+	// commits share it, when `Option.Dedup` is set.
+	let mut Seen = HashMap::<String, (u64, usize)>::new();
+
+	// Key, id, summary and `Oid` of each commit kept, diffed once the walk is
+	// done.
+	let mut Selected = Vec::<(u64, String, String, Oid)>::new();
+
+	for Oid in Walk {
+		if Option.Limit.is_some_and(|Limit| Selected.len() >= Limit) {
+			break;
+		}
+
+		let Commit = Repository.find_commit(Oid?)?;
+
+		let Date = match Option.Date {
+			Date::Author => Commit.author().when(),
+			Date::Committer => Commit.time(),
+		};
+
+		if let Some(Time) = Time(Date) {
+			if Option.Since.is_some_and(|Since| Time < Since)
+				|| Option.Until.is_some_and(|Until| Time > Until)
+			{
+				continue;
+			}
+		}
+
+		let Merged = Commit.parent_count() > 1;
+
+		if match Option.Merge {
+			Merge::Include => false,
+			Merge::Exclude => Merged,
+			Merge::Only => !Merged,
+		} {
+			continue;
+		}
+
+		if !Option.Path.is_empty() && !Difference::Touches(&Repository, &Commit, &Option.Path)? {
+			continue;
+		}
+
+		let Summary = Commit.summary().unwrap_or_default().to_string();
+
+		if Option.Dedup {
+			if let Some((_, Count)) = Seen.get_mut(&Summary) {
+				*Count += 1;
+				continue;
+			}
+		}
+
+		let (Key, Id) = match Option.Key {
+			Key::Index => {
+				(
+					Selected.len() as u64,
+					Commit.as_object().short_id()?.as_str().unwrap_or_default().to_string(),
+				)
+			},
+			Key::Oid => {
+				let mut Key = [0; 8];
+
+				Key.copy_from_slice(&Commit.id().as_bytes()[..8]);
+
+				(u64::from_be_bytes(Key), Commit.id().to_string())
+			},
+		};
+
+		if Option.Dedup {
+			Seen.insert(Summary.clone(), (Key, 1));
+		}
+
+		Selected.push((Key, Id, Summary, Commit.id()));
+	}
+
+	// `Repository` cannot cross threads, so every worker opens its own.
+	let Root = Repository.path().to_path_buf();
+
+	let Diff = || {
+		Selected
+			.into_par_iter()
+			.map_init(
+				|| Repository::open(&Root),
+				|Repository, (Key, Id, Summary, Oid)| {
+					let Repository = Repository.as_ref().map_err(|_Error| {
+						git2::Error::new(_Error.code(), _Error.class(), _Error.message())
+					})?;
+
+					let Commit = Repository.find_commit(Oid)?;
+
+					let Difference = match Option.Stat {
+						true => {
+							let (File, Insertion, Deletion) =
+								Difference::Stats(Repository, &Commit)?;
+
+							format!(
+								"{} {}, +{} -{}",
+								File,
+								if File == 1 { "file" } else { "files" },
+								Insertion,
+								Deletion
+							)
+						},
+						false => Difference::Fn(Repository, &Commit)?,
+					};
+
+					Build.insert(Key, (Id, format!("{}\n\n{}", Summary, Difference)));
+
+					Ok(())
+				},
+			)
+			.collect::<Result<(), Error>>()
+	};
+
+	match Option.Threads {
+		Some(Threads) => {
+			ThreadPoolBuilder::new()
+				.num_threads(Threads)
+				.build()
+				.map_err(|_Error| Error::Pool(_Error.to_string()))?
+				.install(Diff)?
+		},
+		None => Diff()?,
+	}
+
+	for (Summary, (Key, Count)) in Seen.into_iter().filter(|(_, (_, Count))| *Count > 1) {
+		if let Some(mut Entry) = Build.get_mut(&Key) {
+			Entry.1 = Entry.1.replacen(&Summary, &format!("{} (x{})", Summary, Count), 1);
+		}
+	}
+
 	Ok(Build)
 }
+
+/// Finds the commit of the most recent tag reachable from `Start`, by commit
+/// time, ignoring tags that do not point at a commit.
+///
+/// # Errors
+///
+/// This function will return an error if the tags cannot be listed.
+fn Tag(Repository:&Repository, Start:Oid) -> Result<std::option::Option<Oid>, git2::Error> {
+	let mut Last = None::<(i64, Oid)>;
+
+	for Name in Repository.tag_names(None)?.iter().flatten() {
+		let Ok(Commit) = Repository
+			.revparse_single(&format!("refs/tags/{}", Name))
+			.and_then(|Object| Object.peel_to_commit())
+		else {
+			continue;
+		};
+
+		// A tag is reachable when it is its own merge base with `Start`.
+		if Repository.merge_base(Start, Commit.id()).ok() != Some(Commit.id()) {
+			continue;
+		}
+
+		if Last.is_none_or(|(Time, _)| Commit.time().seconds() > Time) {
+			Last = Some((Commit.time().seconds(), Commit.id()));
+		}
+	}
+
+	Ok(Last.map(|(_, Oid)| Oid))
+}
+
+/// Converts a git timestamp into a `DateTime` carrying its stored offset.
+fn Time(Time:git2::Time) -> std::option::Option<DateTime<FixedOffset>> {
+	Some(
+		DateTime::from_timestamp(Time.seconds(), 0)?
+			.with_timezone(&FixedOffset::east_opt(Time.offset_minutes() * 60)?),
+	)
+}
+
+pub mod Difference;
+pub mod First;
+pub mod Group;
+pub mod Insert;
+pub mod Stream;
+
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+	sync::atomic::Ordering,
+};
+
+use chrono::{DateTime, FixedOffset};
+use dashmap::DashMap;
+use git2::{Oid, Repository, Sort};
+use rayon::{
+	iter::{IntoParallelIterator, ParallelIterator},
+	ThreadPoolBuilder,
+};
+
+use crate::Struct::Build::{Date, Error, Key, Merge, Struct as Option, Type};
+
+#[cfg(test)]
+mod Test {
+	#[tokio::test]
+	async fn ThreeCommitsInOrder() {
+		let (Directory, Repository) = Fixture::Init();
+
+		for (Index, Message) in ["first", "second", "third"].into_iter().enumerate() {
+			Fixture::Commit(
+				&Repository,
+				&[("a.txt", Message)],
+				Message,
+				&Fixture::Sign("Ann", 1_700_000_000 + Index as i64),
+			);
+		}
+
+		let Build = Fn(Directory.path().to_str().unwrap(), &Option::default()).await.unwrap();
+
+		assert_eq!(Build.len(), 3);
+
+		for (Key, Message) in ["third", "second", "first"].into_iter().enumerate() {
+			assert!(Build.get(&(Key as u64)).unwrap().1.starts_with(Message));
+		}
+	}
+
+	#[tokio::test]
+	async fn DateRangeIsInclusive() {
+		let (Directory, Repository) = Fixture::Init();
+
+		// Noon on one day, the midnight after it and noon on the next.
+		let Day = 1_699_963_200;
+
+		for (Message, Seconds) in
+			[("monday", Day), ("midnight", Day + 43_200), ("tuesday", Day + 86_400)]
+		{
+			Fixture::Commit(
+				&Repository,
+				&[("a.txt", Message)],
+				Message,
+				&Fixture::Sign("Ann", Seconds),
+			);
+		}
+
+		let Build = Fn(
+			Directory.path().to_str().unwrap(),
+			&Option {
+				Since:DateTime::from_timestamp(Day + 43_200, 0).map(|Time| Time.fixed_offset()),
+				Until:DateTime::from_timestamp(Day + 129_599, 0).map(|Time| Time.fixed_offset()),
+				..Option::default()
+			},
+		)
+		.await
+		.unwrap();
+
+		let mut Summary = Build
+			.iter()
+			.map(|Entry| Entry.value().1.lines().next().unwrap().to_string())
+			.collect::<Vec<_>>();
+
+		Summary.sort();
+
+		assert_eq!(Summary, ["midnight", "tuesday"]);
+	}
+
+	#[tokio::test]
+	async fn OidKeysAreStable() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Ann = Fixture::Sign("Ann", 1_700_000_000);
+
+		for Message in ["first", "second"] {
+			Fixture::Commit(&Repository, &[("a.txt", Message)], Message, &Ann);
+		}
+
+		let Option = Option { Key:Key::Oid, ..Option::default() };
+
+		let Entry = Directory.path().to_str().unwrap();
+
+		let Keys = |Build:&Type| Build.iter().map(|Entry| *Entry.key()).collect::<HashSet<_>>();
+
+		let First = Keys(&Fn(Entry, &Option).await.unwrap());
+
+		assert_eq!(First, Keys(&Fn(Entry, &Option).await.unwrap()));
+
+		Fixture::Commit(&Repository, &[("a.txt", "third")], "third", &Ann);
+
+		let Grown = Fn(Entry, &Option).await.unwrap();
+
+		assert!(Keys(&Grown).is_superset(&First));
+
+		assert!(Grown.iter().all(|Entry| Entry.value().0.len() == 40));
+	}
+
+	#[tokio::test]
+	async fn LimitAppliesAfterFilters() {
+		let (Directory, Repository) = Fixture::Init();
+
+		for Index in 0..10 {
+			Fixture::Commit(
+				&Repository,
+				&[("a.txt", &Index.to_string())],
+				&Index.to_string(),
+				&Fixture::Sign("Ann", 1_700_000_000 + Index),
+			);
+		}
+
+		let Entry = Directory.path().to_str().unwrap();
+
+		let Build = Fn(Entry, &Option { Limit:Some(3), ..Option::default() }).await.unwrap();
+
+		assert_eq!(Build.len(), 3);
+
+		// The three newest commits made before the cut-off, not the three newest.
+		let Build = Fn(
+			Entry,
+			&Option {
+				Limit:Some(3),
+				Until:DateTime::from_timestamp(1_700_000_004, 0).map(|Time| Time.fixed_offset()),
+				..Option::default()
+			},
+		)
+		.await
+		.unwrap();
+
+		let mut Summary = Build
+			.iter()
+			.map(|Entry| Entry.value().1.lines().next().unwrap().to_string())
+			.collect::<Vec<_>>();
+
+		Summary.sort();
+
+		assert_eq!(Summary, ["2", "3", "4"]);
+	}
+
+	#[tokio::test]
+	async fn RevSelectsBranch() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Ann = Fixture::Sign("Ann", 1_700_000_000);
+
+		let Base = Fixture::Commit(&Repository, &[("a.txt", "base")], "base", &Ann);
+
+		Fixture::Commit(&Repository, &[("a.txt", "main")], "main only", &Ann);
+
+		let Main = Repository.head().unwrap().shorthand().unwrap().to_string();
+
+		Repository.branch("release", &Repository.find_commit(Base).unwrap(), false).unwrap();
+
+		Repository.set_head("refs/heads/release").unwrap();
+
+		Fixture::Commit(&Repository, &[("b.txt", "release")], "release only", &Ann);
+
+		Repository.set_head(&format!("refs/heads/{}", Main)).unwrap();
+
+		for (Rev, Expected) in [(Main.as_str(), "main only"), ("release", "release only")] {
+			let Option = Option { Rev:Some(Rev.to_string()), ..Option::default() };
+
+			let mut Summary = Fn(Directory.path().to_str().unwrap(), &Option)
+				.await
+				.unwrap()
+				.iter()
+				.map(|Entry| Entry.value().1.lines().next().unwrap().to_string())
+				.collect::<Vec<_>>();
+
+			Summary.sort();
+
+			assert_eq!(Summary, ["base", Expected]);
+		}
+
+		assert!(matches!(
+			Fn(
+				Directory.path().to_str().unwrap(),
+				&Option { Rev:Some("missing".to_string()), ..Option::default() }
+			)
+			.await,
+			Err(Error::Rev(..))
+		));
+	}
+
+	#[tokio::test]
+	async fn PathKeepsTouchingCommits() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Ann = Fixture::Sign("Ann", 1_700_000_000);
+
+		Fixture::Commit(&Repository, &[("src/a.rs", "a"), ("README", "a")], "root", &Ann);
+
+		Fixture::Commit(&Repository, &[("docs/a.md", "a")], "docs", &Ann);
+
+		Fixture::Commit(&Repository, &[("src/b.rs", "b")], "src", &Ann);
+
+		let mut Summary = Fn(
+			Directory.path().to_str().unwrap(),
+			&Option { Path:vec![PathBuf::from("src")], ..Option::default() },
+		)
+		.await
+		.unwrap()
+		.iter()
+		.map(|Entry| Entry.value().1.lines().next().unwrap().to_string())
+		.collect::<Vec<_>>();
+
+		Summary.sort();
+
+		assert_eq!(Summary, ["root", "src"]);
+	}
+
+	#[tokio::test]
+	async fn StatReplacesPatch() {
+		let (Directory, Repository) = Fixture::Init();
+
+		Fixture::Commit(
+			&Repository,
+			&[("a.txt", "one\n"), ("b.txt", "two\n")],
+			"add",
+			&Fixture::Sign("Ann", 1_700_000_000),
+		);
+
+		let Option = Option { Stat:true, ..Option::default() };
+
+		let Build = Fn(Directory.path().to_str().unwrap(), &Option).await.unwrap();
+
+		assert_eq!(Build.get(&0).unwrap().1, "add\n\n2 files, +2 -0");
+	}
+
+	#[tokio::test]
+	async fn CacheDiscoversOnce() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Ann = Fixture::Sign("Ann", 1_700_000_000);
+
+		Fixture::Commit(&Repository, &[("a.txt", "a")], "add", &Ann);
+
+		let Cache = Arc::new(Cache::default());
+
+		let Option = Option { Cache:Some(Arc::clone(&Cache)), ..Option::default() };
+
+		for _ in 0..2 {
+			assert_eq!(Fn(Directory.path().to_str().unwrap(), &Option).await.unwrap().len(), 1);
+		}
+
+		assert_eq!(Cache.Root.len(), 1);
+
+		assert_eq!(Cache.Hit.load(Ordering::Relaxed), 1);
+	}
+
+	#[tokio::test]
+	async fn EmptyDirectoryIsNotARepository() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Result = Fn(Directory.path().to_str().unwrap(), &Option::default()).await;
+
+		assert!(matches!(Result, Err(Error::NotARepository(_))), "{:?}", Result.map(|_| ()));
+	}
+
+	#[tokio::test]
+	async fn DedupCountsRepeats() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let mut Latest = None;
+
+		let Message = ["fix typo", "add a", "fix typo", "fix typo"];
+
+		for (Index, Message) in Message.into_iter().enumerate() {
+			Latest = Some(Fixture::Commit(
+				&Repository,
+				&[("a.txt", &Index.to_string())],
+				Message,
+				&Fixture::Sign("Ann", 1_700_000_000 + Index as i64),
+			));
+		}
+
+		let Option = Option { Dedup:true, Key:Key::Oid, ..Option::default() };
+
+		let Build = Fn(Directory.path().to_str().unwrap(), &Option).await.unwrap();
+
+		assert_eq!(Build.len(), 2);
+
+		let Repeated = Build
+			.iter()
+			.find(|Entry| Entry.value().1.starts_with("fix typo"))
+			.map(|Entry| Entry.value().clone())
+			.unwrap();
+
+		assert_eq!(Repeated.0, Latest.unwrap().to_string());
+
+		assert!(Repeated.1.starts_with("fix typo (x3)\n\n"), "{}", Repeated.1);
+	}
+
+	#[tokio::test]
+	async fn SinceTagKeepsLaterCommits() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Option = Option { SinceTag:true, ..Option::default() };
+
+		for (Index, Message) in ["first", "second", "third"].into_iter().enumerate() {
+			let Oid = Fixture::Commit(
+				&Repository,
+				&[("a.txt", Message)],
+				Message,
+				&Fixture::Sign("Ann", 1_700_000_000 + Index as i64),
+			);
+
+			if Index == 0 {
+				let Untagged = Fn(Directory.path().to_str().unwrap(), &Option).await.unwrap();
+
+				assert_eq!(Untagged.len(), 1);
+
+				Repository
+					.tag_lightweight("v1.0.0", &Repository.find_object(Oid, None).unwrap(), false)
+					.unwrap();
+			}
+		}
+
+		let Build = Fn(Directory.path().to_str().unwrap(), &Option).await.unwrap();
+
+		assert_eq!(Build.len(), 2);
+
+		for (Key, Message) in ["third", "second"].into_iter().enumerate() {
+			assert!(Build.get(&(Key as u64)).unwrap().1.starts_with(Message));
+		}
+	}
+
+	#[tokio::test]
+	async fn ThreadsDoNotChangeTheResult() {
+		let (Directory, Repository) = Fixture::Init();
+
+		for Index in 0..20 {
+			Fixture::Commit(
+				&Repository,
+				&[(&format!("{}.txt", Index % 3), &format!("line {}\n", Index))],
+				&format!("change {}", Index),
+				&Fixture::Sign("Ann", 1_700_000_000 + Index),
+			);
+		}
+
+		let mut Result = Vec::new();
+
+		for Threads in [Some(1), Some(4), None] {
+			let Option = Option { Threads, ..Option::default() };
+
+			let mut Build = Fn(Directory.path().to_str().unwrap(), &Option)
+				.await
+				.unwrap()
+				.into_iter()
+				.collect::<Vec<_>>();
+
+			Build.sort();
+
+			Result.push(Build);
+		}
+
+		assert_eq!(Result[0].len(), 20);
+
+		assert_eq!(Result[0], Result[1]);
+
+		assert_eq!(Result[0], Result[2]);
+	}
+
+	#[tokio::test]
+	async fn MergePolicyFiltersMerges() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Ann = Fixture::Sign("Ann", 1_700_000_000);
+
+		let Base = Fixture::Commit(&Repository, &[("a.txt", "base")], "base", &Ann);
+
+		let Side = Fixture::Commit(&Repository, &[("b.txt", "side")], "side", &Ann);
+
+		// The branch goes back to `Base`, leaving `Side` to be merged.
+		Repository.head().unwrap().set_target(Base, "rewind").unwrap();
+
+		Fixture::Commit(&Repository, &[("a.txt", "main")], "main", &Ann);
+
+		Fixture::Merge(&Repository, &[], "Merge branch 'side'", &Ann, Side);
+
+		for (Merge, Count) in [(Merge::Include, 4), (Merge::Exclude, 3), (Merge::Only, 1)] {
+			let Build =
+				Fn(Directory.path().to_str().unwrap(), &Option { Merge, ..Option::default() })
+					.await
+					.unwrap();
+
+			assert_eq!(Build.len(), Count, "{:?}", Merge);
+
+			if Merge == Merge::Only {
+				assert!(Build.get(&0).unwrap().1.starts_with("Merge branch 'side'"));
+			}
+		}
+
+		assert_eq!(Option::default().Merge, Merge::Exclude);
+	}
+
+	#[tokio::test]
+	async fn DateSelectsTheTimestamp() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Day = 1_699_963_200;
+
+		// Written on the first day and rebased on the third.
+		Fixture::CommitAs(
+			&Repository,
+			&[("a.txt", "rebased")],
+			"rebased",
+			&Fixture::Sign("Ann", Day),
+			&Fixture::Sign("Ann", Day + 2 * 86_400),
+		);
+
+		let Count = |Date| {
+			let Option = Option {
+				Date,
+				Until:DateTime::from_timestamp(Day + 86_400, 0).map(|Time| Time.fixed_offset()),
+				..Option::default()
+			};
+
+			let Path = Directory.path().to_str().unwrap().to_string();
+
+			async move { Fn(&Path, &Option).await.unwrap().len() }
+		};
+
+		assert_eq!(Count(Date::Author).await, 1);
+
+		assert_eq!(Count(Date::Committer).await, 0);
+
+		assert_eq!(Option::default().Date, Date::Committer);
+	}
+
+	use std::{collections::HashSet, sync::Arc};
+
+	use super::*;
+	use crate::{Fixture, Struct::Build::Cache};
+}
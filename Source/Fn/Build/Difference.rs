@@ -0,0 +1,144 @@
+/// Produces the unified diff introduced by `Commit`.
+///
+/// The commit tree is compared against the tree of its first parent, or
+/// against an empty tree for a root commit, so the very first commit shows
+/// every file it added.
+///
+/// # Arguments
+///
+/// * `Repository` - The repository the commit belongs to.
+/// * `Commit` - The commit to diff.
+///
+/// # Returns
+///
+/// Returns the patch text, with each line prefixed by its `+`, `-` or ` `
+/// origin marker.
+///
+/// # Errors
+///
+/// This function will return an error if either tree cannot be read or the
+/// diff cannot be computed.
+pub fn Fn(Repository:&Repository, Commit:&Commit) -> Result<String, git2::Error> {
+	let Difference = Tree(Repository, Commit)?;
+
+	let mut Patch = String::new();
+
+	Difference.print(DiffFormat::Patch, |_, _, Line| {
+		if let '+' | '-' | ' ' = Line.origin() {
+			Patch.push(Line.origin());
+		}
+
+		Patch.push_str(&String::from_utf8_lossy(Line.content()));
+
+		true
+	})?;
+
+	Ok(Patch)
+}
+
+/// Counts the changes introduced by `Commit`, using the same comparison as
+/// `Fn` without rendering the patch.
+///
+/// # Returns
+///
+/// Returns the number of files changed, lines inserted and lines deleted.
+///
+/// # Errors
+///
+/// This function will return an error if either tree cannot be read or the
+/// diff cannot be computed.
+pub fn Stats(
+	Repository:&Repository,
+	Commit:&Commit,
+) -> Result<(usize, usize, usize), git2::Error> {
+	let Stats = Tree(Repository, Commit)?.stats()?;
+
+	Ok((Stats.files_changed(), Stats.insertions(), Stats.deletions()))
+}
+
+/// Reports whether `Commit` added, changed or removed any file beneath one of
+/// the `Path` prefixes, using the same comparison as `Fn`.
+///
+/// # Errors
+///
+/// This function will return an error if either tree cannot be read or the
+/// diff cannot be computed.
+pub fn Touches(
+	Repository:&Repository,
+	Commit:&Commit,
+	Path:&[PathBuf],
+) -> Result<bool, git2::Error> {
+	Ok(Tree(Repository, Commit)?.deltas().any(|Delta| {
+		[Delta.old_file().path(), Delta.new_file().path()]
+			.into_iter()
+			.flatten()
+			.any(|File| Path.iter().any(|Path| File.starts_with(Path)))
+	}))
+}
+
+/// Diffs the tree of `Commit` against that of its first parent, or against an
+/// empty tree for a root commit.
+fn Tree<'a>(Repository:&'a Repository, Commit:&Commit) -> Result<Diff<'a>, git2::Error> {
+	let Parent = match Commit.parent_count() {
+		0 => None,
+		_ => Some(Commit.parent(0)?.tree()?),
+	};
+
+	Repository.diff_tree_to_tree(Parent.as_ref(), Some(&Commit.tree()?), None)
+}
+
+use std::path::PathBuf;
+
+use git2::{Commit, Diff, DiffFormat, Repository};
+
+#[cfg(test)]
+mod Test {
+	#[test]
+	fn OneLineChangeIsAdded() {
+		let (_Directory, Repository) = Fixture::Init();
+
+		let Ann = Fixture::Sign("Ann", 1_700_000_000);
+
+		Fixture::Commit(&Repository, &[("a.txt", "one\n")], "add", &Ann);
+
+		let Oid = Fixture::Commit(&Repository, &[("a.txt", "one\ntwo\n")], "change", &Ann);
+
+		let Patch = Fn(&Repository, &Repository.find_commit(Oid).unwrap()).unwrap();
+
+		assert!(Patch.lines().any(|Line| Line == "+two"), "{}", Patch);
+
+		assert!(!Patch.lines().any(|Line| Line == "+one"), "{}", Patch);
+	}
+
+	#[test]
+	fn RootCommitAddsEverything() {
+		let (_Directory, Repository) = Fixture::Init();
+
+		let Oid = Fixture::Commit(
+			&Repository,
+			&[("a.txt", "one\n")],
+			"add",
+			&Fixture::Sign("Ann", 1_700_000_000),
+		);
+
+		let Patch = Fn(&Repository, &Repository.find_commit(Oid).unwrap()).unwrap();
+
+		assert!(Patch.lines().any(|Line| Line == "+one"), "{}", Patch);
+	}
+
+	#[test]
+	fn StatsCountOneLineChange() {
+		let (_Directory, Repository) = Fixture::Init();
+
+		let Ann = Fixture::Sign("Ann", 1_700_000_000);
+
+		Fixture::Commit(&Repository, &[("a.txt", "one\ntwo\n")], "add", &Ann);
+
+		let Oid = Fixture::Commit(&Repository, &[("a.txt", "one\nthree\n")], "change", &Ann);
+
+		assert_eq!(Stats(&Repository, &Repository.find_commit(Oid).unwrap()).unwrap(), (1, 1, 1));
+	}
+
+	use super::*;
+	use crate::Fixture;
+}
@@ -0,0 +1,184 @@
+/// Finds the initial commit of the history reachable from `HEAD`.
+///
+/// The walk runs oldest-first so the root commit is found without visiting
+/// the rest of the history. Changelog ranges can be anchored at it.
+///
+/// # Arguments
+///
+/// * `Repository` - The repository to search.
+///
+/// # Returns
+///
+/// Returns the id of the first commit without parents, or `None` if the
+/// repository has no commits yet.
+///
+/// # Errors
+///
+/// This function will return an error if the history cannot be walked.
+pub fn Fn(Repository:&Repository) -> Result<Option<Oid>, git2::Error> {
+	if Repository.is_empty()? {
+		return Ok(None);
+	}
+
+	let mut Walk = Repository.revwalk()?;
+
+	Walk.set_sorting(Sort::TIME | Sort::REVERSE)?;
+
+	Walk.push_head()?;
+
+	for Oid in Walk {
+		let Oid = Oid?;
+
+		if Repository.find_commit(Oid)?.parent_count() == 0 {
+			return Ok(Some(Oid));
+		}
+	}
+
+	Ok(None)
+}
+
+/// Finds the commit that introduced `Path` into the history reachable from
+/// `HEAD`.
+///
+/// The walk runs oldest-first and stops at the first commit whose tree holds
+/// `Path`. When that commit added `Path` by renaming another file, the search
+/// carries on from the old name, so a renamed file reports the age of its
+/// original.
+///
+/// # Arguments
+///
+/// * `Repository` - The repository to search.
+/// * `Path` - The file to look for, relative to the repository root.
+///
+/// # Returns
+///
+/// Returns the id of the commit introducing the file, or `None` if no commit
+/// holds it.
+///
+/// # Errors
+///
+/// This function will return an error if the history, a tree or a diff cannot
+/// be read.
+pub fn ForPath(Repository:&Repository, Path:&Path) -> Result<Option<Oid>, git2::Error> {
+	if Repository.is_empty()? {
+		return Ok(None);
+	}
+
+	let mut Name = Path.to_path_buf();
+
+	let mut Seen = HashSet::new();
+
+	let mut Found = None;
+
+	while Seen.insert(Name.clone()) {
+		let mut Walk = Repository.revwalk()?;
+
+		Walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+		Walk.push_head()?;
+
+		let mut Introduced = None;
+
+		for Oid in Walk {
+			let Commit = Repository.find_commit(Oid?)?;
+
+			if Commit.tree()?.get_path(&Name).is_ok() {
+				Introduced = Some(Commit);
+				break;
+			}
+		}
+
+		let Some(Commit) = Introduced else {
+			break;
+		};
+
+		Found = Some(Commit.id());
+
+		match Renamed(Repository, &Commit, &Name)? {
+			Some(Old) => Name = Old,
+			None => break,
+		}
+	}
+
+	Ok(Found)
+}
+
+/// Returns the old name of `Name` when `Commit` renamed it from another file.
+fn Renamed(
+	Repository:&Repository,
+	Commit:&Commit,
+	Name:&Path,
+) -> Result<Option<PathBuf>, git2::Error> {
+	let Some(Parent) = Commit.parents().next() else {
+		return Ok(None);
+	};
+
+	let mut Difference =
+		Repository.diff_tree_to_tree(Some(&Parent.tree()?), Some(&Commit.tree()?), None)?;
+
+	Difference.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+	Ok(Difference
+		.deltas()
+		.find(|Delta| Delta.status() == Delta::Renamed && Delta.new_file().path() == Some(Name))
+		.and_then(|Delta| Delta.old_file().path().map(Path::to_path_buf)))
+}
+
+use std::{
+	collections::HashSet,
+	path::{Path, PathBuf},
+};
+
+use git2::{Commit, Delta, DiffFindOptions, Oid, Repository, Sort};
+
+#[cfg(test)]
+mod Test {
+	#[test]
+	fn FindsInitialCommit() {
+		let (_Directory, Repository) = Fixture::Init();
+
+		assert_eq!(Fn(&Repository).unwrap(), None);
+
+		let Ann = Fixture::Sign("Ann", 1_700_000_000);
+
+		let Initial = Fixture::Commit(&Repository, &[("a.txt", "one")], "add", &Ann);
+
+		Fixture::Commit(&Repository, &[("a.txt", "two")], "change", &Ann);
+
+		Fixture::Commit(&Repository, &[("b.txt", "one")], "add b", &Ann);
+
+		assert_eq!(Fn(&Repository).unwrap(), Some(Initial));
+	}
+
+	#[test]
+	fn ForPathFollowsRenames() {
+		let (Directory, Repository) = Fixture::Init();
+
+		assert_eq!(ForPath(&Repository, Path::new("b.txt")).unwrap(), None);
+
+		let Ann = Fixture::Sign("Ann", 1_700_000_000);
+
+		Fixture::Commit(&Repository, &[("a.txt", "one")], "add a", &Ann);
+
+		let Content = "first line\nsecond line\nthird line\n";
+
+		let Added = Fixture::Commit(&Repository, &[("b.txt", Content)], "add b", &Ann);
+
+		let Grown = format!("{}fourth line\n", Content);
+
+		Fixture::Commit(&Repository, &[("b.txt", &Grown)], "grow", &Ann);
+
+		assert_eq!(ForPath(&Repository, Path::new("b.txt")).unwrap(), Some(Added));
+
+		std::fs::remove_file(Directory.path().join("b.txt")).unwrap();
+
+		Fixture::Commit(&Repository, &[("c.txt", &Grown)], "rename b", &Ann);
+
+		assert_eq!(ForPath(&Repository, Path::new("c.txt")).unwrap(), Some(Added));
+
+		assert_eq!(ForPath(&Repository, Path::new("d.txt")).unwrap(), None);
+	}
+
+	use super::*;
+	use crate::Fixture;
+}
@@ -0,0 +1,576 @@
+/// Aggregates the commits collected from every repository by author for the
+/// report `Print` writes.
+///
+/// The author of each commit is looked up again in its repository through the
+/// short id stored by `Build::Fn` and resolved through the `.mailmap` of the
+/// repository when it has one. Author keys have their whitespace collapsed
+/// and the domain trimmed from the email, so `Jane  Doe <jane@host>` becomes
+/// `Jane Doe <jane>`.
+///
+/// # Arguments
+///
+/// * `Entry` - A vector of repository paths paired with the map returned by
+///   `Build::Fn` for that repository.
+/// * `Truncate` - An optional number of characters summaries are cut to, the
+///   last of them becoming an ellipsis.
+/// * `Body` - Whether to follow each summary with the rest of its commit
+///   message.
+///
+/// # Returns
+///
+/// Returns a map from author key to the summaries of that author's commits, in
+/// walk order, each followed by its body when `Body` is set.
+///
+/// # Errors
+///
+/// This function will log errors if a repository or commit cannot be read and
+/// skip the affected entries.
+pub fn Fn(
+	Entry:Vec<(String, Type)>,
+	Truncate:Option<usize>,
+	Body:bool,
+) -> BTreeMap<String, Vec<String>> {
+	let mut Group = BTreeMap::<String, Vec<String>>::new();
+
+	for (Entry, Build) in Entry {
+		let Repository = match Repository::discover(&Entry) {
+			Ok(Repository) => Repository,
+			Err(_Error) => {
+				eprintln!("Cannot Repository for {}: {}", Entry, _Error);
+				continue;
+			},
+		};
+
+		let Mailmap = Repository.mailmap().ok();
+
+		let mut Build = Build.into_iter().collect::<Vec<_>>();
+
+		Build.sort_by_key(|(Index, _)| *Index);
+
+		for (_, (Id, Message)) in Build {
+			match Repository.revparse_single(&Id).and_then(|Object| Object.peel_to_commit()) {
+				Ok(Commit) => {
+					let mut Summary = Cut(Message.lines().next().unwrap_or_default(), Truncate);
+
+					// Body lines are indented under the summary they belong to.
+					if let Some(Body) = Commit.body().filter(|_| Body) {
+						for Line in Body.trim_end().lines() {
+							Summary.push_str(&format!("\n  {}", Line));
+						}
+					}
+
+					Group.entry(Resolve(Mailmap.as_ref(), &Commit)).or_default().push(Summary);
+				},
+				Err(_Error) => eprintln!("Cannot Commit {} in {}: {}", Id, Entry, _Error),
+			}
+		}
+	}
+
+	Group
+}
+
+/// Prints the map returned by `Fn`, each author followed by their summaries
+/// indented under them.
+pub fn Print(Group:&BTreeMap<String, Vec<String>>) {
+	for (Author, Summary) in Group {
+		println!("{}", Author);
+
+		for Summary in Summary {
+			for Line in Summary.lines() {
+				println!("\t{}", Line);
+			}
+		}
+	}
+}
+
+/// Aggregates the commits collected from every repository by author into the
+/// number of commits and the lines they inserted and deleted.
+///
+/// Authors are keyed as in `Fn`. Merge commits count towards the commits of
+/// their author but not towards the lines, as their changes are already
+/// counted in the commits they merge.
+///
+/// # Arguments
+///
+/// * `Entry` - A vector of repository paths paired with the map returned by
+///   `Build::Fn` for that repository.
+///
+/// # Returns
+///
+/// Returns a map from author key to their commits, insertions and deletions.
+///
+/// # Errors
+///
+/// This function will log errors if a repository, commit or diff cannot be
+/// read and skip the affected entries.
+pub fn Contribution(Entry:Vec<(String, Type)>) -> BTreeMap<String, (usize, usize, usize)> {
+	let mut Group = BTreeMap::<String, (usize, usize, usize)>::new();
+
+	for (Entry, Build) in Entry {
+		let Repository = match Repository::discover(&Entry) {
+			Ok(Repository) => Repository,
+			Err(_Error) => {
+				eprintln!("Cannot Repository for {}: {}", Entry, _Error);
+				continue;
+			},
+		};
+
+		let Mailmap = Repository.mailmap().ok();
+
+		for (_, (Id, _)) in Build {
+			let Commit =
+				match Repository.revparse_single(&Id).and_then(|Object| Object.peel_to_commit()) {
+					Ok(Commit) => Commit,
+					Err(_Error) => {
+						eprintln!("Cannot Commit {} in {}: {}", Id, Entry, _Error);
+						continue;
+					},
+				};
+
+			let Total = Group.entry(Resolve(Mailmap.as_ref(), &Commit)).or_default();
+
+			Total.0 += 1;
+
+			if Commit.parent_count() > 1 {
+				continue;
+			}
+
+			match super::Difference::Stats(&Repository, &Commit) {
+				Ok((_, Insertion, Deletion)) => {
+					Total.1 += Insertion;
+					Total.2 += Deletion;
+				},
+				Err(_Error) => eprintln!("Cannot Stats {} in {}: {}", Id, Entry, _Error),
+			}
+		}
+	}
+
+	Group
+}
+
+/// Renders the totals returned by `Contribution` as a leaderboard, one
+/// `1. Jane Doe <jane> — 4 commits, +120 -30` line per author ranked by
+/// insertions, then by author.
+///
+/// # Arguments
+///
+/// * `Group` - The map returned by `Contribution`.
+///
+/// # Returns
+///
+/// Returns the leaderboard text.
+pub fn Leaderboard(Group:&BTreeMap<String, (usize, usize, usize)>) -> String {
+	let mut Rank = Group.iter().collect::<Vec<_>>();
+
+	Rank.sort_by(|(_, (_, Left, _)), (_, (_, Right, _))| Right.cmp(Left));
+
+	Rank.into_iter()
+		.enumerate()
+		.map(|(Place, (Author, (Commit, Insertion, Deletion)))| {
+			format!(
+				"{}. {} — {} {}, +{} -{}\n",
+				Place + 1,
+				Author,
+				Commit,
+				if *Commit == 1 { "commit" } else { "commits" },
+				Insertion,
+				Deletion
+			)
+		})
+		.collect()
+}
+
+/// Aggregates the commits collected from every repository into changelog
+/// sections by their conventional-commit type.
+///
+/// Summaries such as `feat(api): add paging` land under `Features` as
+/// `<id> api: add paging`; summaries without a recognized prefix land under
+/// `Other` unchanged.
+///
+/// # Arguments
+///
+/// * `Entry` - A vector of repository paths paired with the map returned by
+///   `Build::Fn` for that repository.
+///
+/// # Returns
+///
+/// Returns a map from section title to the id and description of each commit
+/// in it, in walk order.
+pub fn Changelog(Entry:Vec<(String, Type)>) -> BTreeMap<String, Vec<String>> {
+	let mut Group = BTreeMap::<String, Vec<String>>::new();
+
+	for (_, Build) in Entry {
+		let mut Build = Build.into_iter().collect::<Vec<_>>();
+
+		Build.sort_by_key(|(Index, _)| *Index);
+
+		for (_, (Id, Message)) in Build {
+			let Summary = Message.lines().next().unwrap_or_default();
+
+			let (Title, Description) = match Conventional(Summary) {
+				// An unknown type such as `wip:` is part of the summary itself.
+				Some((Kind, ..)) if Section(Kind) == "Other" => ("Other", Summary.to_string()),
+				Some((Kind, Scope, Description)) => {
+					(
+						Section(Kind),
+						match Scope {
+							Some(Scope) => format!("{}: {}", Scope, Description),
+							None => Description.to_string(),
+						},
+					)
+				},
+				None => ("Other", Summary.to_string()),
+			};
+
+			Group.entry(Title.to_string()).or_default().push(format!("{} {}", Id, Description));
+		}
+	}
+
+	Group
+}
+
+/// Renders the sections returned by `Changelog` as Markdown, one `## <Title>`
+/// header per section in `Order` and one bullet per commit.
+///
+/// Each id is written as `[abc1234]`, and becomes a link to `<Url>/abc1234`
+/// when a `Url` such as `https://github.com/owner/repo/commit` is given.
+///
+/// # Arguments
+///
+/// * `Group` - The map returned by `Changelog`.
+/// * `Url` - An optional prefix the ids are appended to.
+///
+/// # Returns
+///
+/// Returns the Markdown document.
+pub fn Markdown(Group:&BTreeMap<String, Vec<String>>, Url:Option<&str>) -> String {
+	let mut Markdown = String::from("# Changelog\n");
+
+	for (Title, Summary) in Order(Group) {
+		Markdown.push_str(&format!("\n## {}\n\n", Title));
+
+		for Summary in Summary {
+			let (Id, Description) = Summary.split_once(' ').unwrap_or((Summary, ""));
+
+			match Url {
+				Some(Url) => {
+					Markdown.push_str(&format!(
+						"- [{}]({}/{}) {}\n",
+						Id,
+						Url.trim_end_matches('/'),
+						Id,
+						Description
+					))
+				},
+				None => Markdown.push_str(&format!("- [{}] {}\n", Id, Description)),
+			}
+		}
+	}
+
+	Markdown
+}
+
+/// Section titles in the order a changelog lists them.
+pub const SECTION:[(&str, &str); 11] = [
+	("feat", "Features"),
+	("fix", "Bug Fixes"),
+	("perf", "Performance"),
+	("refactor", "Refactoring"),
+	("revert", "Reverts"),
+	("docs", "Documentation"),
+	("test", "Tests"),
+	("build", "Build"),
+	("ci", "CI"),
+	("style", "Style"),
+	("chore", "Chores"),
+];
+
+/// Maps a conventional-commit type to its section title, `Other` when unknown.
+pub fn Section(Kind:&str) -> &'static str {
+	SECTION
+		.iter()
+		.find(|(Prefix, _)| Prefix.eq_ignore_ascii_case(Kind))
+		.map_or("Other", |(_, Title)| Title)
+}
+
+/// Iterates the sections of `Group` in `SECTION` order, then any others
+/// alphabetically, then `Other`.
+pub fn Order(
+	Group:&BTreeMap<String, Vec<String>>,
+) -> impl Iterator<Item = (&String, &Vec<String>)> {
+	let Rank = |Title:&str| {
+		SECTION
+			.iter()
+			.position(|(_, Known)| *Known == Title)
+			.unwrap_or(if Title == "Other" { SECTION.len() + 1 } else { SECTION.len() })
+	};
+
+	let mut Group = Group.iter().collect::<Vec<_>>();
+
+	Group.sort_by_key(|(Title, _)| Rank(Title));
+
+	Group.into_iter()
+}
+
+/// Splits a `type(scope)!: description` summary into its type, optional scope
+/// and description.
+fn Conventional(Summary:&str) -> Option<(&str, Option<&str>, &str)> {
+	let (Head, Description) = Summary.split_once(':')?;
+
+	let Head = Head.strip_suffix('!').unwrap_or(Head);
+
+	let (Kind, Scope) = match Head.split_once('(') {
+		Some((Kind, Scope)) => (Kind, Some(Scope.strip_suffix(')')?.trim())),
+		None => (Head, None),
+	};
+
+	if Kind.is_empty() || !Kind.chars().all(|Character| Character.is_ascii_alphabetic()) {
+		return None;
+	}
+
+	Some((Kind, Scope.filter(|Scope| !Scope.is_empty()), Description.trim()))
+}
+
+/// Cuts `Summary` to `Truncate` characters, ending it in an ellipsis when
+/// anything was cut.
+fn Cut(Summary:&str, Truncate:Option<usize>) -> String {
+	match Truncate {
+		Some(Truncate) if Summary.chars().count() > Truncate => {
+			let mut Cut = Summary.chars().take(Truncate.saturating_sub(1)).collect::<String>();
+
+			Cut.push('…');
+
+			Cut
+		},
+		_ => Summary.to_string(),
+	}
+}
+
+/// Resolves the author of `Commit` through `Mailmap` when there is one and
+/// normalizes it into its grouping key.
+fn Resolve(Mailmap:Option<&Mailmap>, Commit:&Commit) -> String {
+	Author(
+		&Mailmap
+			.and_then(|Mailmap| Commit.author_with_mailmap(Mailmap).ok())
+			.unwrap_or_else(|| Commit.author().to_owned()),
+	)
+}
+
+/// Normalizes a signature into its grouping key.
+fn Author(Signature:&Signature) -> String {
+	let Name = Signature.name().unwrap_or_default().split_whitespace().collect::<Vec<_>>().join(" ");
+
+	match Signature.email().and_then(|Email| Email.split('@').next()).map(str::trim) {
+		Some(Email) if !Email.is_empty() => format!("{} <{}>", Name, Email),
+		_ => Name,
+	}
+}
+
+use std::collections::BTreeMap;
+
+use git2::{Commit, Mailmap, Repository, Signature};
+
+use crate::Struct::Build::Type;
+
+#[cfg(test)]
+mod Test {
+	#[test]
+	fn ChangelogSections() {
+		let Build = Type::new();
+
+		for (Index, Message) in
+			["feat(api): add paging", "fix: crash on start", "wip: foo", "Update readme"]
+				.into_iter()
+				.enumerate()
+		{
+			Build.insert(Index as u64, (format!("c{}", Index), format!("{}\n\ndiff", Message)));
+		}
+
+		let Group = Changelog(vec![("Repository".to_string(), Build)]);
+
+		assert_eq!(Group["Features"], ["c0 api: add paging"]);
+
+		assert_eq!(Group["Bug Fixes"], ["c1 crash on start"]);
+
+		assert_eq!(Group["Other"], ["c2 wip: foo", "c3 Update readme"]);
+
+		assert_eq!(Group.len(), 3);
+	}
+
+	#[test]
+	fn BucketsByAuthor() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Build = Type::new();
+
+		for (Index, (Name, Message)) in
+			[("Ann", "one"), ("Bob", "two"), ("Ann", "three")].into_iter().enumerate()
+		{
+			let Oid = Fixture::Commit(
+				&Repository,
+				&[("a.txt", Message)],
+				Message,
+				&Fixture::Sign(Name, 1_700_000_000 + Index as i64),
+			);
+
+			Build.insert(Index as u64, (Oid.to_string(), format!("{}\n\ndiff", Message)));
+		}
+
+		let Group = Fn(vec![(Directory.path().to_string_lossy().to_string(), Build)], None, false);
+
+		assert_eq!(Group.len(), 2);
+
+		assert_eq!(Group["Ann <ann>"], ["one", "three"]);
+
+		assert_eq!(Group["Bob <bob>"], ["two"]);
+	}
+
+	#[test]
+	fn ChangelogOrdersSections() {
+		let Build = Type::new();
+
+		let Message = [
+			"chore: bump",
+			"docs(readme): typo",
+			"Merge branch",
+			"feat!: drop node 16",
+			"fix: leak",
+		];
+
+		for (Index, Message) in Message.into_iter().enumerate() {
+			Build.insert(Index as u64, (format!("c{}", Index), Message.to_string()));
+		}
+
+		let Group = Changelog(vec![("Repository".to_string(), Build)]);
+
+		assert_eq!(Group["Features"], ["c3 drop node 16"]);
+
+		assert_eq!(Group["Documentation"], ["c1 readme: typo"]);
+
+		assert_eq!(
+			Order(&Group).map(|(Title, _)| Title.as_str()).collect::<Vec<_>>(),
+			["Features", "Bug Fixes", "Documentation", "Chores", "Other"]
+		);
+	}
+
+	#[test]
+	fn MarkdownListsFeatures() {
+		let Group = BTreeMap::from([
+			(
+				"Features".to_string(),
+				vec!["abc1234 add paging".to_string(), "def5678 add search".to_string()],
+			),
+			("Other".to_string(), vec!["0123456 Update readme".to_string()]),
+		]);
+
+		let Plain = Markdown(&Group, None);
+
+		assert!(
+			Plain.contains("\n## Features\n\n- [abc1234] add paging\n- [def5678] add search\n"),
+			"{}",
+			Plain
+		);
+
+		assert!(Plain.find("## Features") < Plain.find("## Other"));
+
+		assert!(Markdown(&Group, Some("https://example.com/commit/"))
+			.contains("- [abc1234](https://example.com/commit/abc1234) add paging\n"));
+	}
+
+	#[test]
+	fn MailmapMergesIdentities() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Build = Type::new();
+
+		let Mailmap = "Ann Lee <ann@example.com> <ann@old.example.com>\n";
+
+		for (Index, (Author, Content)) in [
+			(Signature::new("Ann", "ann@old.example.com", &Time::new(1, 0)).unwrap(), "one"),
+			(Signature::new("Ann Lee", "ann@example.com", &Time::new(2, 0)).unwrap(), "two"),
+		]
+		.into_iter()
+		.enumerate()
+		{
+			let Oid = Fixture::Commit(
+				&Repository,
+				&[(".mailmap", Mailmap), ("a.txt", Content)],
+				Content,
+				&Author,
+			);
+
+			Build.insert(Index as u64, (Oid.to_string(), Content.to_string()));
+		}
+
+		let Group = Fn(vec![(Directory.path().to_string_lossy().to_string(), Build)], None, false);
+
+		assert_eq!(Group.len(), 1, "{:?}", Group);
+
+		assert_eq!(Group["Ann Lee <ann>"], ["one", "two"]);
+	}
+
+	#[test]
+	fn ContributionTotalsByAuthor() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Build = Type::new();
+
+		let History = [
+			("Ann", "one\ntwo\nthree\n"),
+			("Bob", "one\n2\nthree\n"),
+			("Ann", "one\n2\nthree\nfour\n"),
+		];
+
+		for (Index, (Name, Content)) in History.into_iter().enumerate() {
+			let Oid = Fixture::Commit(
+				&Repository,
+				&[("a.txt", Content)],
+				Name,
+				&Fixture::Sign(Name, 1_700_000_000 + Index as i64),
+			);
+
+			Build.insert(Index as u64, (Oid.to_string(), Name.to_string()));
+		}
+
+		let Group = Contribution(vec![(Directory.path().to_string_lossy().to_string(), Build)]);
+
+		assert_eq!(Group["Ann <ann>"], (2, 4, 0));
+
+		assert_eq!(Group["Bob <bob>"], (1, 1, 1));
+
+		assert!(Leaderboard(&Group).starts_with("1. Ann <ann>"));
+	}
+
+	#[test]
+	fn SubjectOnlyByDefault() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Oid = Fixture::Commit(
+			&Repository,
+			&[("a.txt", "one")],
+			"Add the parser\n\nExplains why.\nAnd what else.\n",
+			&Fixture::Sign("Ann", 1_700_000_000),
+		);
+
+		let Group = |Truncate, Body| {
+			let Build = Type::new();
+
+			Build.insert(0, (Oid.to_string(), "Add the parser\n\ndiff".to_string()));
+
+			Fn(vec![(Directory.path().to_string_lossy().to_string(), Build)], Truncate, Body)
+				.remove("Ann <ann>")
+				.unwrap()
+		};
+
+		assert_eq!(Group(None, false), ["Add the parser"]);
+
+		assert_eq!(Group(Some(8), false), ["Add the…"]);
+
+		assert_eq!(Group(None, true), ["Add the parser\n  Explains why.\n  And what else."]);
+	}
+
+	use git2::Time;
+
+	use super::*;
+	use crate::Fixture;
+}
@@ -0,0 +1,290 @@
+/// Persists the commits collected by `Build::Fn` as a JSON array.
+///
+/// Each commit becomes an `{ "index", "id", "message" }` object. The array is
+/// sorted on the index so the output is identical across runs regardless of
+/// how the map was sharded, and it is written to `<Path>.tmp` first and then
+/// renamed over `Path` so readers never observe a partial file.
+///
+/// # Arguments
+///
+/// * `Path` - The file to write.
+/// * `Build` - The map returned by `Build::Fn`.
+///
+/// # Errors
+///
+/// This function will return an error if the JSON cannot be written or the
+/// temporary file cannot be renamed.
+pub fn Fn(Path:&Path, Build:&Type) -> Result<(), Box<dyn std::error::Error>> {
+	Write(Path, &Object(Build))
+}
+
+/// Merges the commits collected by `Build::Fn` into the JSON array at `Path`.
+///
+/// Entries already in the file stay where they are, and only commits whose
+/// `id` is not among them are appended, sorted on the index as in `Fn`. This
+/// lets a store grow run after run, such as nightly with `SinceTag`, without
+/// walking the old history again. The ids are matched exactly, so every run
+/// appending to one store should collect them with the same `Key`.
+///
+/// A missing file is started fresh, and so is one that does not hold a JSON
+/// array, after a warning on standard error.
+///
+/// # Arguments
+///
+/// * `Path` - The file to merge into.
+/// * `Build` - The map returned by `Build::Fn`.
+///
+/// # Returns
+///
+/// How many commits were appended.
+///
+/// # Errors
+///
+/// This function will return an error if the existing file cannot be read for
+/// any reason other than being missing, or if the merged JSON cannot be
+/// written.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use std::path::Path;
+/// # use Library::{Fn::Build::Insert::Append, Struct::Build::Type};
+/// # fn Run(Build:&Type) -> Result<(), Box<dyn std::error::Error>> {
+/// let Appended = Append(Path::new("Commit.json"), &Build)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn Append(Path:&Path, Build:&Type) -> Result<usize, Box<dyn std::error::Error>> {
+	let mut Insert = match fs::read(Path) {
+		Ok(Existing) => {
+			serde_json::from_slice::<Vec<Value>>(&Existing).unwrap_or_else(|_Error| {
+				eprintln!("Cannot read {} as a store, starting fresh: {}", Path.display(), _Error);
+
+				Vec::new()
+			})
+		},
+		Err(_Error) if _Error.kind() == ErrorKind::NotFound => Vec::new(),
+		Err(_Error) => return Err(_Error.into()),
+	};
+
+	let Seen = Insert
+		.iter()
+		.filter_map(|Entry| Entry["id"].as_str().map(str::to_string))
+		.collect::<HashSet<_>>();
+
+	let Unseen = Object(Build)
+		.into_iter()
+		.filter(|Entry| Entry["id"].as_str().is_none_or(|Id| !Seen.contains(Id)))
+		.collect::<Vec<_>>();
+
+	let Appended = Unseen.len();
+
+	Insert.extend(Unseen);
+
+	Write(Path, &Insert)?;
+
+	Ok(Appended)
+}
+
+/// Upserts the commits collected by `Build::Fn` into the SQLite database at
+/// `Path`, so the commits of many repositories can be queried together.
+///
+/// Rows go into a `commits(sha TEXT PRIMARY KEY, repo TEXT, message TEXT, diff
+/// TEXT)` table, created when absent, where `message` is the summary and `diff`
+/// whatever `Build::Fn` stored after it. A commit already in the table has its
+/// row replaced. Every row is written in a single transaction, so a failure
+/// leaves the database as it was.
+///
+/// # Arguments
+///
+/// * `Path` - The database to open or create. `:memory:` opens a private
+///   in-memory one.
+/// * `Repo` - The repository the commits were collected from.
+/// * `Build` - The map returned by `Build::Fn`.
+///
+/// # Returns
+///
+/// How many commits were upserted.
+///
+/// # Errors
+///
+/// This function will return an error if the database cannot be opened, the
+/// table cannot be created or any row cannot be written.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use std::path::Path;
+/// # use Library::{Fn::Build::Insert::SQLite, Struct::Build::Type};
+/// # fn Run(Build:&Type) -> Result<(), Box<dyn std::error::Error>> {
+/// let Upserted = SQLite(Path::new("Commit.db"), "Rest", &Build)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn SQLite(Path:&Path, Repo:&str, Build:&Type) -> Result<usize, Box<dyn std::error::Error>> {
+	let mut Connection = Connection::open(Path)?;
+
+	let Transaction = Connection.transaction()?;
+
+	Transaction.execute(
+		"CREATE TABLE IF NOT EXISTS commits (sha TEXT PRIMARY KEY, repo TEXT, message TEXT, diff \
+		 TEXT)",
+		[],
+	)?;
+
+	let mut Upserted = 0;
+
+	{
+		let mut Statement = Transaction.prepare(
+			"INSERT INTO commits (sha, repo, message, diff) VALUES (?1, ?2, ?3, ?4) ON CONFLICT \
+			 (sha) DO UPDATE SET repo = excluded.repo, message = excluded.message, diff = \
+			 excluded.diff",
+		)?;
+
+		for Entry in Build.iter() {
+			let (Id, Message) = Entry.value();
+
+			let (Summary, Difference) = Message.split_once("\n\n").unwrap_or((Message, ""));
+
+			Upserted += Statement.execute(params![Id, Repo, Summary, Difference])?;
+		}
+	}
+
+	Transaction.commit()?;
+
+	Ok(Upserted)
+}
+
+/// Turns the commits of `Build` into `{ "index", "id", "message" }` objects
+/// sorted on the index.
+fn Object(Build:&Type) -> Vec<Value> {
+	let mut Entry = Build
+		.iter()
+		.map(|Entry| (*Entry.key(), Entry.value().clone()))
+		.collect::<Vec<_>>();
+
+	Entry.sort_by_key(|(Index, _)| *Index);
+
+	Entry
+		.into_iter()
+		.map(|(Index, (Id, Message))| json!({ "index": Index, "id": Id, "message": Message }))
+		.collect()
+}
+
+/// Writes `Insert` to `<Path>.tmp` and renames it over `Path`.
+fn Write(Path:&Path, Insert:&[Value]) -> Result<(), Box<dyn std::error::Error>> {
+	let mut Temporary = Path.as_os_str().to_owned();
+
+	Temporary.push(".tmp");
+
+	fs::write(&Temporary, serde_json::to_vec_pretty(Insert)?)?;
+
+	fs::rename(&Temporary, Path)?;
+
+	Ok(())
+}
+
+use std::{collections::HashSet, fs, io::ErrorKind, path::Path};
+
+use rusqlite::{params, Connection};
+use serde_json::{json, Value};
+
+use crate::Struct::Build::Type;
+
+#[cfg(test)]
+mod Test {
+	#[test]
+	fn RoundTrip() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Path = Directory.path().join("Commit.json");
+
+		let Build = Type::new();
+
+		for Index in [2, 0, 1] {
+			Build.insert(Index, (format!("c{}", Index), format!("message {}", Index)));
+		}
+
+		Fn(&Path, &Build).unwrap();
+
+		assert!(!Directory.path().join("Commit.json.tmp").exists());
+
+		let Read = serde_json::from_slice::<Vec<Value>>(&fs::read(&Path).unwrap()).unwrap();
+
+		assert_eq!(Read, Object(&Build));
+
+		assert_eq!(
+			Read.iter().map(|Entry| Entry["index"].as_u64().unwrap()).collect::<Vec<_>>(),
+			[0, 1, 2]
+		);
+
+		assert_eq!(Read[1], json!({ "index": 1, "id": "c1", "message": "message 1" }));
+	}
+
+	#[test]
+	fn AppendAddsOnlyUnseen() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Path = Directory.path().join("Commit.json");
+
+		fs::write(&Path, "not json").unwrap();
+
+		let Build = Type::new();
+
+		for Index in 0..2 {
+			Build.insert(Index, (format!("c{}", Index), format!("message {}", Index)));
+		}
+
+		assert_eq!(Append(&Path, &Build).unwrap(), 2);
+
+		Build.insert(2, ("c2".to_string(), "message 2".to_string()));
+
+		assert_eq!(Append(&Path, &Build).unwrap(), 1);
+
+		let Read = serde_json::from_slice::<Vec<Value>>(&fs::read(&Path).unwrap()).unwrap();
+
+		assert_eq!(
+			Read.iter().map(|Entry| Entry["id"].as_str().unwrap()).collect::<Vec<_>>(),
+			["c0", "c1", "c2"]
+		);
+
+		assert_eq!(Append(&Path, &Build).unwrap(), 0);
+	}
+
+	#[test]
+	fn SQLiteUpsertsEveryCommit() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Path = Directory.path().join("Commit.db");
+
+		let Build = Type::new();
+
+		for Index in 0..3 {
+			Build.insert(Index, (format!("c{}", Index), format!("message {}\n\ndiff", Index)));
+		}
+
+		assert_eq!(SQLite(&Path, "Rest", &Build).unwrap(), 3);
+
+		Build.insert(0, ("c0".to_string(), "reworded\n\ndiff".to_string()));
+
+		assert_eq!(SQLite(&Path, "Rest", &Build).unwrap(), 3);
+
+		let Connection = Connection::open(&Path).unwrap();
+
+		let Count = Connection
+			.query_row("SELECT COUNT(*) FROM commits", [], |Row| Row.get::<_, i64>(0))
+			.unwrap();
+
+		assert_eq!(Count, 3);
+
+		let Row = Connection
+			.query_row("SELECT message, diff FROM commits WHERE sha = 'c0'", [], |Row| {
+				Ok((Row.get::<_, String>(0)?, Row.get::<_, String>(1)?))
+			})
+			.unwrap();
+
+		assert_eq!(Row, ("reworded".to_string(), "diff".to_string()));
+	}
+
+	use super::*;
+}
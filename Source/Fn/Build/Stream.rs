@@ -0,0 +1,79 @@
+/// Writes the commits collected by `Build::Fn` to standard output as JSON
+/// lines.
+///
+/// Each commit becomes one `{ "repo", "sha", "message" }` object on its own
+/// line, sorted on its key, where `message` is the summary of the commit. That
+/// is walk order with `Key::Index`, while `Key::Oid` keys sort in no order
+/// meaningful to history. A repository is written whole once its walk is
+/// done, not commit by commit, under a single lock of standard output so the
+/// lines of concurrent repositories never interleave.
+///
+/// # Arguments
+///
+/// * `Entry` - The path of the repository the commits were collected from.
+/// * `Build` - The map returned by `Build::Fn`.
+///
+/// # Errors
+///
+/// This function will return an error if standard output cannot be written.
+pub fn Fn(Entry:&str, Build:&Type) -> std::io::Result<()> {
+	Line(&mut std::io::stdout().lock(), Entry, Build)
+}
+
+/// Does the work of `Fn` on `Output`.
+fn Line(Output:&mut impl Write, Entry:&str, Build:&Type) -> std::io::Result<()> {
+	let mut Build = Build
+		.iter()
+		.map(|Entry| (*Entry.key(), Entry.value().clone()))
+		.collect::<Vec<_>>();
+
+	Build.sort_by_key(|(Index, _)| *Index);
+
+	for (_, (Id, Message)) in Build {
+		let Summary = Message.lines().next().unwrap_or_default();
+
+		writeln!(Output, "{}", json!({ "repo": Entry, "sha": Id, "message": Summary }))?;
+	}
+
+	Output.flush()
+}
+
+use std::io::Write;
+
+use serde_json::json;
+
+use crate::Struct::Build::Type;
+
+#[cfg(test)]
+mod Test {
+	#[test]
+	fn EveryLineParses() {
+		let Build = Type::new();
+
+		let Message = ["feat: a\n\ndiff --git", "fix: \"quoted\"\n\n+x"];
+
+		for (Index, Message) in Message.into_iter().enumerate() {
+			Build.insert(Index as u64, (format!("c{}", Index), Message.to_string()));
+		}
+
+		let mut Output = Vec::new();
+
+		Line(&mut Output, "path/to/repo", &Build).unwrap();
+
+		let Line = String::from_utf8(Output)
+			.unwrap()
+			.lines()
+			.map(|Line| serde_json::from_str::<serde_json::Value>(Line).unwrap())
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			Line,
+			[
+				json!({ "repo": "path/to/repo", "sha": "c0", "message": "feat: a" }),
+				json!({ "repo": "path/to/repo", "sha": "c1", "message": "fix: \"quoted\"" }),
+			]
+		);
+	}
+
+	use super::*;
+}
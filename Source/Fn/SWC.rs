@@ -1,64 +1,331 @@
-async fn Fn() -> anyhow::Result<()> {
-	tracing_subscriber::fmt::init();
+pub async fn Fn() -> anyhow::Result<()> {
+	let Command = Command::Fn();
 
-	let args:Vec<String> = std::env::args().collect();
-	if args.len() != 2 {
-		error!("Usage: {} <directory>", args[0]);
-		std::process::exit(1);
+	tracing_subscriber::fmt()
+		.with_writer(std::io::stderr)
+		.with_max_level(Verbosity(Command.get_count("Verbose"), Command.get_count("Quiet")))
+		.init();
+
+	let Path = std::path::PathBuf::from(
+		Command.get_one::<String>("Directory").map_or(".", String::as_str),
+	);
+
+	let Source = match Command.get_one::<String>("Config") {
+		Some(Config) => Some(std::path::PathBuf::from(Config)),
+		None if fs::try_exists(CompilerConfig::PATH).await.unwrap_or(false) => {
+			Some(CompilerConfig::PATH.into())
+		},
+		None if fs::try_exists(CompilerConfig::TSCONFIG).await.unwrap_or(false) => {
+			Some(CompilerConfig::TSCONFIG.into())
+		},
+		None => None,
+	};
+
+	let Config = match &Source {
+		Some(Source) => CompilerConfig::from_path(Source).await,
+		None => Ok(CompilerConfig::default()),
 	}
+	.and_then(CompilerConfig::env);
 
-	let Path = std::path::PathBuf::from(&args[1]);
+	let Config = match Config {
+		Ok(Config) => Config,
+		Err(_Error) => {
+			error!("{:#}", _Error);
+			std::process::exit(1);
+		},
+	};
+
+	if Path.as_os_str() == "-" {
+		let mut Input = String::new();
+
+		tokio::io::stdin().read_to_string(&mut Input).await?;
 
-	let Config = if let Ok(Config) = fs::read_to_string("swc_config.json").await {
-		serde_json::from_str(&Config).unwrap_or_default()
-	} else {
-		CompilerConfig::default()
+		match SWC::Compiler::new(Config).compile_str("stdin.ts", Input) {
+			Ok(Output) => {
+				for Warning in &Output.warnings {
+					warn!("{}", Warning);
+				}
+
+				// Flushed through the handle it was written to, as another one
+				// would not wait for the write before the exit.
+				let mut Stdout = tokio::io::stdout();
+				Stdout.write_all(Output.code.as_bytes()).await?;
+				Stdout.flush().await?;
+				std::process::exit(0);
+			},
+			Err(_Error) => {
+				error!("{:#}", _Error);
+				std::process::exit(1);
+			},
+		}
+	}
+
+	let Single = fs::metadata(&Path).await.is_ok_and(|Metadata| Metadata.is_file());
+
+	// A bare file name is anchored to the current directory, so the pattern
+	// built from it cannot match a longer name ending the same way.
+	let Path = match Single && Path.parent() == Some(std::path::Path::new("")) {
+		true => std::path::Path::new(".").join(Path),
+		false => Path,
 	};
 
-	let options = Option {
+	let Default = vec![".ts".to_string(), ".tsx".to_string()];
+
+	// Plain JavaScript is only picked up when named, as its output would
+	// otherwise be compiled again on the next run.
+	let Accepted = [Default.as_slice(), &[".js".to_string(), ".jsx".to_string()]].concat();
+
+	if Single && !SWC::Patterns::new(&Accepted)?.is_match(&Path) {
+		error!("{} is not a TypeScript or JavaScript source", Path.display());
+		std::process::exit(1);
+	}
+
+	let mut options = Option {
 		entry:vec![vec![Path.to_string_lossy().to_string()]],
-		separator:std::path::MAIN_SEPARATOR,
-		pattern:".ts".to_string(),
+		// Only the single file itself, not its neighbours, when watching it.
+		patterns:match Single {
+			true => vec![Path.to_string_lossy().to_string()],
+			false => Default,
+		},
 		config:Config.clone(),
+		config_path:Source,
+		force:Command.get_flag("Force"),
+		debounce_ms:150,
+		ignore:vec!["node_modules".to_string(), ".git".to_string()],
+		concurrency:num_cpus::get(),
+		batch_size:Command.get_one::<usize>("BatchSize").copied().unwrap_or(1),
+		report_path:Command.get_one::<String>("Report").map(std::path::PathBuf::from),
+		dry_run:Command.get_flag("DryRun"),
+		on_compiled:Command.get_one::<String>("OnCompiled").cloned(),
+		quiet:Command.get_count("Quiet") > 0,
+		retry_reads:false,
+		exclude_tests:Command.get_flag("ExcludeTests"),
+		test_patterns:Option::TEST_PATTERNS.map(str::to_string).to_vec(),
+		metrics:None,
+		metrics_flush_secs:Command.get_one::<u64>("FlushEvery").copied().unwrap_or(30),
+		fail_fast:Command.get_flag("FailFast"),
+		per_file_timeout_secs:Command.get_one::<u64>("Timeout").copied().unwrap_or(60),
+		bundle:Command.get_one::<String>("Bundle").map(std::path::PathBuf::from),
+		cache_path:SWC::Cache::PATH.into(),
 	};
 
+	if let Some(Rev) = Command.get_one::<String>("ChangedSince") {
+		let (Changed, Removed) = match Changed::Fn(&Path, Rev) {
+			Ok(Difference) => Difference,
+			Err(_Error) => {
+				error!("{:#}", _Error);
+				std::process::exit(1);
+			},
+		};
+
+		let Pattern = SWC::Patterns::new(&options.patterns)?;
+
+		let Outputs = SWC::Compiler::new(options.config.clone());
+
+		for File in Removed.iter().filter(|File| Pattern.is_match(File)) {
+			Watch::Remove(File, &Outputs).await;
+		}
+
+		info!("Compiling {} files changed since {}...", Changed.len(), Rev);
+
+		let Run = Watch::Compile::Fn(Option {
+			entry:Changed.iter().map(|File| vec![File.to_string_lossy().to_string()]).collect(),
+			force:true,
+			..options
+		})
+		.await?;
+
+		Finish(Command.get_one::<String>("CIReport"), &Run).await;
+	}
+
+	let FilesFrom = Command.get_one::<String>("FilesFrom");
+
+	if let Some(List) = FilesFrom {
+		let List = match fs::read_to_string(List).await {
+			Ok(List) => List,
+			Err(_Error) => {
+				error!("Cannot read {}: {}", List, _Error);
+				std::process::exit(1);
+			},
+		};
+
+		options.entry = List
+			.lines()
+			.map(str::trim)
+			.filter(|Line| !Line.is_empty() && !Line.starts_with('#'))
+			.map(|Line| {
+				std::path::Path::new(Line)
+					.components()
+					.map(|Entry| Entry.as_os_str().to_string_lossy().to_string())
+					.collect()
+			})
+			.collect();
+	}
+
+	if Command.get_flag("List") {
+		let mut Output = tokio::io::stdout();
+
+		for File in Watch::Compile::Entries(&options)? {
+			Output.write_all(format!("{}\n", File).as_bytes()).await?;
+		}
+
+		Output.flush().await?;
+
+		std::process::exit(0);
+	}
+
+	if FilesFrom.is_some() {
+		let Run = Watch::Compile::Fn(options).await?;
+
+		Finish(Command.get_one::<String>("CIReport"), &Run).await;
+	}
+
 	// Initial compilation
 	info!("Starting initial compilation...");
-	Watch::Compile::Fn(options.clone()).await?;
+	let Run = Watch::Compile::Fn(options.clone()).await?;
+
+	if !Command.get_flag("Watch") {
+		Finish(Command.get_one::<String>("CIReport"), &Run).await;
+	}
 
 	info!("Initial compilation complete. Watching for changes...");
 
 	// Start watching for changes
-	Watch::Fn(Path, options).await?;
+	Watch::Fn(Path, options, Signal()).await?;
+
+	info!("Stopped watching.");
 
 	Ok(())
 }
 
-pub mod Watch;
+/// Writes the summary of `Run` to `Report`, or to standard error when it is
+/// `-`, then exits with `1` if any file failed and `0` otherwise.
+async fn Finish(Report:std::option::Option<&String>, Run:&SWC::CompilerRun) -> ! {
+	if let Some(Report) = Report {
+		match serde_json::to_vec_pretty(&Run.summary()) {
+			Ok(mut Summary) => {
+				Summary.push(b'\n');
 
-use std::{
-	path::Path,
-	sync::Arc,
-	time::{Duration, Instant, SystemTime},
-};
+				let Written = match Report.as_str() {
+					// Flushed before the exit below, which would cut the write off.
+					"-" => {
+						async {
+							let mut Stderr = tokio::io::stderr();
+
+							Stderr.write_all(&Summary).await?;
+
+							Stderr.flush().await
+						}
+						.await
+					},
+					_ => fs::write(Report, &Summary).await,
+				};
+
+				if let Err(_Error) = Written {
+					error!("Cannot write CI report {}: {}", Report, _Error);
+				}
+			},
+			Err(_Error) => error!("Cannot serialize CI report: {}", _Error),
+		}
+	}
+
+	std::process::exit(if Run.report.errors > 0 { 1 } else { 0 });
+}
+
+/// Flips the returned receiver to `true` on the first SIGINT, or SIGTERM on
+/// Unix.
+fn Signal() -> watch::Receiver<bool> {
+	let (Allow, Mark) = watch::channel(false);
+
+	tokio::spawn(async move {
+		#[cfg(unix)]
+		let Terminate = async {
+			match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+				Ok(mut Terminate) => {
+					Terminate.recv().await;
+				},
+				Err(_Error) => {
+					error!("Cannot listen for SIGTERM: {}", _Error);
+					std::future::pending::<()>().await;
+				},
+			}
+		};
+
+		#[cfg(not(unix))]
+		let Terminate = std::future::pending::<()>();
+
+		tokio::select! {
+			_ = signal::ctrl_c() => {},
+			_ = Terminate => {},
+		}
+
+		info!("Shutting down...");
+
+		let _ = Allow.send(true);
+	});
+
+	Mark
+}
+
+/// The most detailed level logged once `Verbose` or `Quiet` is given that
+/// many times, `info` with neither. A single `Quiet` only hides the progress
+/// bar and per-file lines, so it keeps `info` and the summary logged there.
+fn Verbosity(Verbose:u8, Quiet:u8) -> Level {
+	match (Verbose, Quiet) {
+		(0, 0 | 1) => Level::INFO,
+		(0, 2) => Level::WARN,
+		(0, _) => Level::ERROR,
+		(1, _) => Level::DEBUG,
+		_ => Level::TRACE,
+	}
+}
+
+pub mod Changed;
+pub mod Command;
+pub mod Watch;
 
-use futures::stream::FuturesUnordered;
-use notify::{Config, RecommendedWatcher, RecursiveMode};
-use serde::{Deserialize, Serialize};
-use swc_common::{FileName, FilePathMapping, Mark, SourceMap, Span, DUMMY_SP};
-use swc_ecma_ast::EsVersion;
-use swc_ecma_codegen::{text_writer::JsWriter, Emitter};
-use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax, TsConfig};
-use swc_ecma_transforms_base::{
-	helpers::{inject_helpers, InjectHelpers},
-	resolver,
-};
-use swc_ecma_transforms_proposal::decorators;
 use tokio::{
 	fs,
-	sync::{mpsc, Mutex},
-	task,
+	io::{AsyncReadExt, AsyncWriteExt},
+	signal,
+	sync::watch,
 };
-use tracing::{debug, error, info, instrument, warn};
+use tracing::{error, info, warn, Level};
+
+use crate::Struct::SWC::{self, CompilerConfig, Option};
+
+#[cfg(test)]
+mod Test {
+	#[test]
+	fn VerbosityFiltersDebug() {
+		let Enabled = |Count:u8| {
+			let Subscriber =
+				tracing_subscriber::fmt().with_max_level(Verbosity(Count, 0)).finish();
+
+			tracing::subscriber::with_default(Subscriber, || {
+				(
+					tracing::enabled!(Level::INFO),
+					tracing::enabled!(Level::DEBUG),
+					tracing::enabled!(Level::TRACE),
+				)
+			})
+		};
 
-use crate::Struct::SWC;
+		assert_eq!(Enabled(0), (true, false, false));
+
+		assert_eq!(Enabled(1), (true, true, false));
+
+		assert_eq!(Enabled(2), (true, true, true));
+	}
+
+	#[test]
+	fn QuietFiltersInfo() {
+		assert_eq!(Verbosity(0, 1), Level::INFO);
+
+		assert_eq!(Verbosity(0, 2), Level::WARN);
+
+		assert_eq!(Verbosity(0, 3), Level::ERROR);
+	}
+
+	use super::*;
+}
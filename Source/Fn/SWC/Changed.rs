@@ -0,0 +1,162 @@
+/// Lists the files under `Root` that differ between the tree of `Rev` and the
+/// working directory, including untracked ones, split into those that still
+/// exist and those that were deleted.
+///
+/// # Errors
+///
+/// This function will return an error if `Root` is not inside a repository,
+/// `Rev` does not resolve to a tree, or the diff cannot be computed.
+pub fn Fn(Root:&Path, Rev:&str) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+	let Repository = Repository::discover(Root)
+		.with_context(|| format!("Cannot find a repository for {}", Root.display()))?;
+
+	let Work = Repository
+		.workdir()
+		.ok_or_else(|| anyhow!("Cannot diff the bare repository {}", Repository.path().display()))?
+		.to_path_buf();
+
+	let Tree = Repository
+		.revparse_single(Rev)
+		.and_then(|Object| Object.peel_to_tree())
+		.with_context(|| format!("Cannot resolve {}", Rev))?;
+
+	let mut Difference = Repository.diff_tree_to_workdir_with_index(
+		Some(&Tree),
+		Some(DiffOptions::new().include_untracked(true).recurse_untracked_dirs(true)),
+	)?;
+
+	// Pairs deletions with the additions they were moved to, untracked ones
+	// included, so both sides of a rename are reported.
+	Difference.find_similar(Some(DiffFindOptions::new().renames(true).for_untracked(true)))?;
+
+	let Root = std::fs::canonicalize(Root)?;
+
+	let Work = std::fs::canonicalize(Work)?;
+
+	let mut Changed = Vec::new();
+
+	let mut Removed = Vec::new();
+
+	for Delta in Difference.deltas() {
+		let (List, File) = match Delta.status() {
+			Delta::Deleted => (&mut Removed, Delta.old_file().path()),
+			Delta::Renamed => {
+				Removed.extend(Delta.old_file().path().map(|File| Work.join(File)));
+
+				(&mut Changed, Delta.new_file().path())
+			},
+			_ => (&mut Changed, Delta.new_file().path()),
+		};
+
+		List.extend(File.map(|File| Work.join(File)).filter(|File| File.starts_with(&Root)));
+	}
+
+	Removed.retain(|File| File.starts_with(&Root));
+
+	Ok((Changed, Removed))
+}
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use git2::{Delta, DiffFindOptions, DiffOptions, Repository};
+
+#[cfg(test)]
+mod Test {
+	#[test]
+	fn RenameReportsBothSides() {
+		let (Directory, Repository) = Fixture::Init();
+
+		Fixture::Commit(
+			&Repository,
+			&[("a.ts", "export const a: number = 1;\n")],
+			"feat: a",
+			&Fixture::Sign("Ann", 0),
+		);
+
+		std::fs::rename(Directory.path().join("a.ts"), Directory.path().join("b.ts")).unwrap();
+
+		let (Changed, Removed) = Fn(Directory.path(), "HEAD").unwrap();
+
+		let Name = |List:&[PathBuf]| -> Vec<String> {
+			List.iter()
+				.filter_map(|File| File.file_name())
+				.map(|Name| Name.to_string_lossy().into())
+				.collect()
+		};
+
+		assert_eq!(Name(&Changed), ["b.ts"]);
+
+		assert_eq!(Name(&Removed), ["a.ts"]);
+	}
+
+	#[test]
+	fn OnlyTouchedFilesSinceRev() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Ann = Fixture::Sign("Ann", 0);
+
+		Fixture::Commit(
+			&Repository,
+			&[("a.ts", "export const a = 1;\n"), ("b.ts", "export const b = 1;\n")],
+			"feat: a and b",
+			&Ann,
+		);
+
+		Fixture::Commit(&Repository, &[("a.ts", "export const a = 2;\n")], "fix: a", &Ann);
+
+		let Root = std::fs::canonicalize(Directory.path()).unwrap();
+
+		assert_eq!(Fn(Directory.path(), "HEAD~1").unwrap(), (vec![Root.join("a.ts")], Vec::new()));
+
+		assert_eq!(Fn(Directory.path(), "HEAD").unwrap(), (Vec::new(), Vec::new()));
+
+		std::fs::remove_file(Directory.path().join("b.ts")).unwrap();
+
+		assert_eq!(Fn(Directory.path(), "HEAD").unwrap(), (Vec::new(), vec![Root.join("b.ts")]));
+
+		assert!(Fn(Directory.path(), "HEAD~5").is_err());
+	}
+
+	#[tokio::test]
+	async fn RemovedUnderNestedConfigIsCleanedUp() {
+		let (Directory, Repository) = Fixture::Init();
+
+		let Package = std::fs::canonicalize(Directory.path()).unwrap().join("pkg");
+
+		let Config = serde_json::json!({ "OutDir": Package.join("out"), "Root": Package });
+
+		Fixture::Commit(
+			&Repository,
+			&[
+				("pkg/swc_config.json", &Config.to_string()),
+				("pkg/a.ts", "export const a = 1;\n"),
+			],
+			"feat: a",
+			&Fixture::Sign("Ann", 0),
+		);
+
+		let Option = Fixture::Project(Directory.path());
+
+		Watch::Compile::Fn(Option.clone()).await.unwrap();
+
+		assert!(Package.join("out/a.js").exists());
+
+		std::fs::remove_file(Package.join("a.ts")).unwrap();
+
+		let (_, Removed) = Fn(Directory.path(), "HEAD").unwrap();
+
+		assert_eq!(Removed, [Package.join("a.ts")]);
+
+		let Outputs = Compiler::new(Option.config.clone());
+
+		for File in &Removed {
+			Watch::Remove(File, &Outputs).await;
+		}
+
+		assert!(!Package.join("out/a.js").exists());
+	}
+
+	use super::*;
+	use crate::{Fixture, Fn::SWC::Watch, Struct::SWC::Compiler};
+}
@@ -0,0 +1,238 @@
+/// Creates and returns the command-line argument matches for the TypeScript
+/// compiler.
+///
+/// This function sets up the command-line interface using the `clap` crate,
+/// defining the directory to compile and watch and the optional path of the
+/// compiler configuration.
+///
+/// # Returns
+///
+/// Returns an `ArgMatches` instance containing the parsed command-line
+/// arguments.
+///
+/// # Arguments
+///
+/// * `Directory` - An argument naming the directory to compile, or `-` to
+///   compile standard input to standard output, required unless `FilesFrom`
+///   is given.
+/// * `Config` - An optional argument naming the configuration file, a
+///   `tsconfig.json` included. When absent the first present of
+///   `swc_config.json` and then `tsconfig.json` in the working directory is
+///   used, otherwise the defaults.
+/// * `Watch` - An optional flag to keep recompiling on changes after the
+///   initial compilation instead of exiting with its status.
+/// * `DryRun` - An optional flag to compile every file without writing any
+///   output.
+/// * `OnCompiled` - An optional command run after each compiled file, with
+///   `{input}` and `{output}` replaced by its paths.
+/// * `Quiet` - An optional counted flag hiding the progress bar and per-file
+///   lines with `-Q`, lowering the log level to `warn`, which drops the
+///   summary, with `-QQ` and to `error` with `-QQQ`.
+/// * `ChangedSince` - An optional git revision, compiling only the files
+///   changed since it and removing the output of those deleted.
+/// * `FilesFrom` - An optional file listing the paths to compile, one per
+///   line, skipping blank lines and those starting with `#`.
+/// * `FailFast` - An optional flag to stop at the first file that fails to
+///   compile.
+/// * `Force` - An optional flag to recompile every file, even those the cache
+///   or the age of their output say are up to date.
+/// * `List` - An optional flag to print the files that would be compiled, one
+///   per line, instead of compiling them.
+/// * `CIReport` - An optional path a JSON summary of the run is written to
+///   once it completes without watching, standard error when given as `-` or
+///   without a value.
+/// * `Bundle` - An optional path the output of every compiled file is
+///   concatenated into, in order of their paths.
+/// * `Report` - An optional path the metrics report of the run is written to,
+///   and rewritten every `FlushEvery` seconds while watching.
+/// * `FlushEvery` - An optional number of seconds between rewrites of
+///   `Report` while watching, `0` only writing it on exit. Default is 30.
+/// * `ExcludeTests` - An optional flag to skip test files such as `*.test.ts`,
+///   `*.spec.ts` and those under `__tests__`.
+/// * `BatchSize` - An optional number of files each spawned task compiles one
+///   after another. Default is 1.
+/// * `Timeout` - An optional number of seconds one file may take to compile
+///   before it is failed and the run moves on, `0` never. Default is 60.
+/// * `Verbose` - An optional counted flag raising the log level from `info` to
+///   `debug` with `-v` and to `trace`, which logs every compiled file, with
+///   `-vv`.
+///
+/// # Errors
+///
+/// This function will exit the process with a usage message if the arguments
+/// cannot be parsed.
+pub fn Fn() -> ArgMatches {
+	Command::new("RestSWC")
+		.version(env!("CARGO_PKG_VERSION"))
+		.author("🖋️ Source — 👐🏻 Open — <Source/Open@PlayForm.Cloud>")
+		.about("⛱️ Rest —")
+		.arg(
+			Arg::new("Directory")
+				.display_order(1)
+				.value_name("DIRECTORY")
+				.required_unless_present("FilesFrom")
+				.help("📂 Directory —"),
+		)
+		.arg(
+			Arg::new("Config")
+				.short('C')
+				.long("Config")
+				.display_order(2)
+				.value_name("CONFIG")
+				.required(false)
+				.help("⚙️ Config —"),
+		)
+		.arg(
+			Arg::new("Watch")
+				.short('W')
+				.long("Watch")
+				.display_order(3)
+				.action(ArgAction::SetTrue)
+				.help("👁️ Watch —"),
+		)
+		.arg(
+			Arg::new("DryRun")
+				.short('D')
+				.long("DryRun")
+				.display_order(4)
+				.action(ArgAction::SetTrue)
+				.help("🧪 DryRun —"),
+		)
+		.arg(
+			Arg::new("OnCompiled")
+				.short('O')
+				.long("OnCompiled")
+				.display_order(5)
+				.value_name("COMMAND")
+				.required(false)
+				.help("🪝 OnCompiled —"),
+		)
+		.arg(
+			Arg::new("Quiet")
+				.short('Q')
+				.long("Quiet")
+				.display_order(6)
+				.action(ArgAction::Count)
+				.conflicts_with("Verbose")
+				.help("🤫 Quiet —"),
+		)
+		.arg(
+			Arg::new("ChangedSince")
+				.short('S')
+				.long("ChangedSince")
+				.display_order(7)
+				.value_name("REF")
+				.required(false)
+				.help("🔀 ChangedSince —"),
+		)
+		.arg(
+			Arg::new("FilesFrom")
+				.short('L')
+				.long("FilesFrom")
+				.display_order(8)
+				.value_name("PATH")
+				.required(false)
+				.help("📋 FilesFrom —"),
+		)
+		.arg(
+			Arg::new("FailFast")
+				.short('F')
+				.long("FailFast")
+				.display_order(9)
+				.action(ArgAction::SetTrue)
+				.help("🛑 FailFast —"),
+		)
+		.arg(
+			Arg::new("Force")
+				.short('f')
+				.long("Force")
+				.display_order(10)
+				.action(ArgAction::SetTrue)
+				.help("🔨 Force —"),
+		)
+		.arg(
+			Arg::new("List")
+				.short('l')
+				.long("List")
+				.display_order(11)
+				.action(ArgAction::SetTrue)
+				.conflicts_with_all(["ChangedSince", "Watch"])
+				.help("📜 List —"),
+		)
+		.arg(
+			Arg::new("CIReport")
+				.short('R')
+				.long("CIReport")
+				.display_order(12)
+				.value_name("PATH")
+				.num_args(0..=1)
+				.default_missing_value("-")
+				.required(false)
+				.help("🧾 CIReport —"),
+		)
+		.arg(
+			Arg::new("Bundle")
+				.short('b')
+				.long("Bundle")
+				.display_order(13)
+				.value_name("FILE")
+				.required(false)
+				.help("📦 Bundle —"),
+		)
+		.arg(
+			Arg::new("Report")
+				.short('r')
+				.long("Report")
+				.display_order(14)
+				.value_name("PATH")
+				.required(false)
+				.help("📈 Report —"),
+		)
+		.arg(
+			Arg::new("FlushEvery")
+				.long("FlushEvery")
+				.display_order(15)
+				.value_name("SECONDS")
+				.value_parser(value_parser!(u64))
+				.default_value("30")
+				.help("⏱️ FlushEvery —"),
+		)
+		.arg(
+			Arg::new("ExcludeTests")
+				.short('T')
+				.long("ExcludeTests")
+				.display_order(16)
+				.action(ArgAction::SetTrue)
+				.help("🧫 ExcludeTests —"),
+		)
+		.arg(
+			Arg::new("BatchSize")
+				.short('B')
+				.long("BatchSize")
+				.display_order(17)
+				.value_name("FILES")
+				.value_parser(value_parser!(usize))
+				.default_value("1")
+				.help("📚 BatchSize —"),
+		)
+		.arg(
+			Arg::new("Timeout")
+				.long("Timeout")
+				.display_order(18)
+				.value_name("SECONDS")
+				.value_parser(value_parser!(u64))
+				.default_value("60")
+				.help("⌛ Timeout —"),
+		)
+		.arg(
+			Arg::new("Verbose")
+				.short('v')
+				.long("Verbose")
+				.display_order(19)
+				.action(ArgAction::Count)
+				.help("🔊 Verbose —"),
+		)
+		.get_matches()
+}
+
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
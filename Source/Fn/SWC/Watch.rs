@@ -1,53 +1,599 @@
 pub mod Compile;
 
-#[tracing::instrument]
-pub async fn Fn(Path:PathBuf, Option:Option) -> notify::Result<()> {
+/// How long shutdown waits for in-flight compilations before abandoning them.
+pub const GRACE:Duration = Duration::from_secs(10);
+
+/// Watches `Path` and recompiles what changes until `Shutdown` flips to
+/// `true`, then waits up to [`GRACE`] for in-flight compilations, each of
+/// which saves the incremental cache as it finishes.
+#[tracing::instrument(skip(Shutdown))]
+pub async fn Fn(
+	Path:PathBuf,
+	mut Option:Option,
+	mut Shutdown:watch::Receiver<bool>,
+) -> notify::Result<()> {
 	let (tx, mut rx) = mpsc::unbounded_channel();
 
-	notify::recommended_watcher::new(
+	let mut Watcher = RecommendedWatcher::new(
 		move |Result| {
-			let _ = futures::executor::block_on(async {
-				tx.send(Result).unwrap();
-			});
+			let _ = tx.send(Result);
 		},
 		notify::Config::default(),
-	)?
-	.watch(Path.as_ref(), notify::RecursiveMode::Recursive)?;
-
-	while let Some(Result) = rx.recv().await {
-		match Result {
-			Ok(event) => {
-				if let notify::Event {
-					kind: notify::EventKind::Modify(notify::event::ModifyKind::Data(_)),
-					paths,
-					..
-				} = event
-				{
-					for path in paths {
-						if path.extension().map_or(false, |ext| ext == "ts") {
-							tokio::task::spawn(async move {
-								if let Err(e) = Compile::Fn(Option {
-									entry:vec![vec![path.to_string_lossy().to_string()]],
-									..Option.clone()
-								})
-								.await
-								{
-									error!("Compilation error: {}", e);
-								}
-							});
+	)?;
+
+	let Ignore = Ignore::new(&Option.ignored(), &Option.roots())
+		.map_err(|e| notify::Error::generic(&e.to_string()))?;
+
+	let Pattern =
+		Patterns::new(&Option.patterns).map_err(|e| notify::Error::generic(&e.to_string()))?;
+
+	// Directories are watched one by one rather than recursively, so a
+	// symlink leading back up the tree is watched once instead of forever.
+	let mut Seen = HashSet::<PathBuf>::new();
+
+	// A single file is watched through its directory alone, as editors often
+	// save by replacing the file.
+	let Single = Path.is_file();
+
+	match Single {
+		true => Watcher.watch(Path.parent().unwrap_or(&Path), notify::RecursiveMode::NonRecursive)?,
+		false => Descend(&mut Watcher, &Path, &mut Seen, &Ignore)?,
+	}
+
+	// The directory holding the config is watched rather than the file itself,
+	// as editors often save by replacing the file.
+	let Config = Option.config_path.as_ref().and_then(|Config| std::fs::canonicalize(Config).ok());
+
+	if let Some(Parent) = Config.as_deref().and_then(Path::parent) {
+		if !std::fs::canonicalize(&Path).is_ok_and(|Path| Parent.starts_with(Path)) {
+			Watcher.watch(Parent, notify::RecursiveMode::NonRecursive)?;
+		}
+	}
+
+	let Window = Duration::from_millis(Option.debounce_ms);
+
+	Option.retry_reads = true;
+
+	let Metrics = Arc::clone(Option.metrics.get_or_insert_with(Default::default));
+
+	let Flush = Option.report_path.clone().filter(|_| Option.metrics_flush_secs > 0).map(|Report| {
+		Flush(Arc::clone(&Metrics), Report, Duration::from_secs(Option.metrics_flush_secs))
+	});
+
+	// Editors emit several events per save, so each path is only dispatched
+	// once it has been quiet for the whole window.
+	let mut Pending = HashMap::<PathBuf, Instant>::new();
+
+	let mut Task = JoinSet::new();
+
+	loop {
+		let Deadline = Pending.values().min().map(|Last| *Last + Window);
+
+		tokio::select! {
+			Result = rx.recv() => {
+				match Result {
+					Some(Ok(notify::Event { kind, paths, .. })) => {
+						if let Some(Config) = Config.as_deref().filter(|Config| {
+							matches!(kind, EventKind::Create(_) | EventKind::Modify(_))
+								&& paths.iter().any(|path| Same(path, Config))
+						}) {
+							match CompilerConfig::from_path(Config).await.and_then(CompilerConfig::env) {
+								Ok(Config) => {
+									info!("Config changed, recompiling everything.");
+
+									Option.config = Config;
+
+									Rebuild(&mut Task, Option.clone());
+								},
+								Err(e) => error!("Keeping the previous config: {:#}", e),
+							}
+
+							continue;
+						}
+
+						let mut Created = Vec::new();
+						let mut Removed = Vec::new();
+
+						match kind {
+							EventKind::Create(_) | EventKind::Modify(ModifyKind::Data(_)) => {
+								Created = paths;
+							},
+
+							EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+								let mut paths = paths.into_iter();
+
+								Removed.extend(paths.next());
+								Created.extend(paths);
+							},
+
+							EventKind::Modify(ModifyKind::Name(RenameMode::From)) | EventKind::Remove(_) => {
+								Removed = paths;
+							},
+
+							EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+								Created = paths;
+							},
+
+							// FSEvents reports either side of a rename alone, without
+							// saying which, so each path counts by whether it is still there.
+							EventKind::Modify(ModifyKind::Name(_)) => {
+								(Created, Removed) = paths.into_iter().partition(|path| path.exists());
+							},
+
+							_ => {},
+						}
+
+						if !Removed.is_empty() {
+							Seen.retain(|Directory| Directory.exists());
+						}
+
+						for path in Created.iter().filter(|path| !Single && path.is_dir() && !Ignore.is_match(path)) {
+							if let Err(e) = Descend(&mut Watcher, path, &mut Seen, &Ignore) {
+								error!("Cannot watch {}: {:?}", path.display(), e);
+							}
+						}
+
+						// Fresh for each event, so edited nested configs are read again.
+						let Outputs = Compiler::new(Option.config.clone());
+
+						for path in Removed.into_iter().filter(|path| Tracked(path, &Pattern, &Ignore)) {
+							Pending.remove(&path);
+
+							Remove(&path, &Outputs).await;
+						}
+
+						for path in Created.into_iter().filter(|path| Tracked(path, &Pattern, &Ignore)) {
+							Pending.insert(path, Instant::now());
 						}
-					}
+					},
+
+					Some(Err(e)) => error!("Watch error: {:?}", e),
+
+					None => break,
 				}
 			},
 
-			Err(e) => error!("Watch error: {:?}", e),
+			_ = Quiet(Deadline) => {
+				let Now = Instant::now();
+
+				let Ready = Pending
+					.iter()
+					.filter(|(_, Last)| Now.duration_since(**Last) >= Window)
+					.map(|(path, _)| path.clone())
+					.collect::<Vec<_>>();
+
+				for path in Ready {
+					Pending.remove(&path);
+
+					Dispatch(&mut Task, path, Option.clone());
+				}
+			},
+
+			Some(_) = Task.join_next(), if !Task.is_empty() => {},
+
+			_ = Stop(&mut Shutdown) => break,
 		}
 	}
 
+	drop(Watcher);
+
+	if !Task.is_empty() {
+		info!("Waiting for {} compilations to finish...", Task.len());
+
+		if timeout(GRACE, async { while Task.join_next().await.is_some() {} }).await.is_err() {
+			warn!("Abandoning {} compilations still running.", Task.len());
+
+			Task.shutdown().await;
+		}
+	}
+
+	if let Some(Flush) = Flush {
+		Flush.abort();
+	}
+
+	if let Some(Report) = &Option.report_path {
+		Snapshot(&Metrics, Report).await;
+	}
+
 	Ok(())
 }
 
-use notify::RecommendedWatcher;
-use tracing::error;
+/// Spawns the task writing the report of `Metrics` to `Report` every `Every`,
+/// from one interval in, until it is aborted.
+fn Flush(Metrics:Arc<Mutex<CompilerMetrics>>, Report:PathBuf, Every:Duration) -> JoinHandle<()> {
+	tokio::spawn(async move {
+		let mut Tick = interval(Every);
+
+		Tick.tick().await;
+
+		loop {
+			Tick.tick().await;
+
+			Snapshot(&Metrics, &Report).await;
+		}
+	})
+}
+
+/// Writes the report of `Metrics` to `Report`, cloning them under a brief lock
+/// so runs are not held up by the write.
+async fn Snapshot(Metrics:&Mutex<CompilerMetrics>, Report:&Path) {
+	let Metrics = Metrics.lock().await.clone();
+
+	match serde_json::to_vec_pretty(&Metrics.report()) {
+		Ok(Content) => {
+			if let Err(e) = fs::write(Report, Content).await {
+				error!("Cannot write report {}: {}", Report.display(), e);
+			}
+		},
+		Err(e) => error!("Cannot serialize report: {}", e),
+	}
+}
+
+/// Watches `Directory` and every directory beneath it that is not ignored,
+/// skipping with a warning any whose canonical path is already in `Seen`.
+fn Descend(
+	Watcher:&mut RecommendedWatcher,
+	Directory:&Path,
+	Seen:&mut HashSet<PathBuf>,
+	Ignore:&Ignore,
+) -> notify::Result<()> {
+	let Ok(Canonical) = std::fs::canonicalize(Directory) else {
+		return Ok(());
+	};
+
+	if !Seen.insert(Canonical.clone()) {
+		warn!("Skipping {}, {} is already watched.", Directory.display(), Canonical.display());
+
+		return Ok(());
+	}
+
+	Watcher.watch(Directory, notify::RecursiveMode::NonRecursive)?;
+
+	for Entry in std::fs::read_dir(Directory).into_iter().flatten().flatten() {
+		let Entry = Entry.path();
+
+		if Entry.is_dir() && !Ignore.is_match(&Entry) {
+			Descend(Watcher, &Entry, Seen, Ignore)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Whether `path` is a source file the watcher compiles.
+fn Tracked(path:&Path, Pattern:&Patterns, Ignore:&Ignore) -> bool {
+	Pattern.is_match(path) && !Ignore.is_match(path)
+}
+
+/// Deletes the JavaScript, source map and declarations `Compiler` emitted for
+/// a removed `path`, where the nearest `swc_config.json` above it put them.
+pub async fn Remove(path:&Path, Compiler:&Compiler) {
+	let Output = match Compiler.Emitted(&path.to_string_lossy()).await {
+		Ok((Output, _)) => Output,
+		Err(e) => {
+			error!("Cannot locate the output of {}: {}", path.display(), e);
+			return;
+		},
+	};
+
+	for Output in [Output.with_extension("js.map"), Output.with_extension("d.ts"), Output] {
+		match fs::remove_file(&Output).await {
+			Ok(()) => info!("Removed: {}", Output.display()),
+			Err(e) if e.kind() == ErrorKind::NotFound => {},
+			Err(e) => error!("Cannot remove {}: {}", Output.display(), e),
+		}
+	}
+}
+
+/// Spawns a compilation of the single file at `path` onto `Task`.
+fn Dispatch(Task:&mut JoinSet<()>, path:PathBuf, Option:Option) {
+	Task.spawn(async move {
+		if let Err(e) = Compile::Fn(Option {
+			entry:vec![vec![path.to_string_lossy().to_string()]],
+			..Option
+		})
+		.await
+		{
+			error!("Compilation error: {}", e);
+		}
+	});
+}
+
+/// Spawns a forced compilation of every entry in `Option` onto `Task`.
+fn Rebuild(Task:&mut JoinSet<()>, Option:Option) {
+	Task.spawn(async move {
+		if let Err(e) = Compile::Fn(Option { force:true, ..Option }).await {
+			error!("Compilation error: {}", e);
+		}
+	});
+}
+
+/// Whether the event `path` names the canonical `Config`.
+fn Same(path:&Path, Config:&Path) -> bool {
+	path.file_name() == Config.file_name()
+		&& std::fs::canonicalize(path).is_ok_and(|path| path == Config)
+}
+
+/// Resolves at `Deadline`, or never when nothing is pending.
+async fn Quiet(Deadline:std::option::Option<Instant>) {
+	match Deadline {
+		Some(Deadline) => sleep_until(Deadline).await,
+		None => std::future::pending().await,
+	}
+}
+
+/// Resolves once `Shutdown` is `true`, or never when its sender is gone.
+async fn Stop(Shutdown:&mut watch::Receiver<bool>) {
+	if Shutdown.wait_for(|Shutdown| *Shutdown).await.is_err() {
+		std::future::pending().await
+	}
+}
+
+use std::{
+	collections::{HashMap, HashSet},
+	io::ErrorKind,
+	path::{Path, PathBuf},
+	sync::Arc,
+	time::Duration,
+};
+
+use notify::{
+	event::{ModifyKind, RenameMode},
+	EventKind, RecommendedWatcher, Watcher,
+};
+use tokio::{
+	fs,
+	sync::{mpsc, watch, Mutex},
+	task::{JoinHandle, JoinSet},
+	time::{interval, sleep_until, timeout, Instant},
+};
+use tracing::{error, info, warn};
+
+use crate::Struct::SWC::{Compiler, CompilerConfig, CompilerMetrics, Ignore, Patterns};
 
 use super::SWC::Option;
+
+#[cfg(test)]
+mod Test {
+	/// Watches the entry of `Option`, giving the watches a moment to register,
+	/// and returns the switch stopping it along with the watcher.
+	async fn Start(Option:Option) -> (watch::Sender<bool>, JoinHandle<notify::Result<()>>) {
+		let (Switch, Shutdown) = watch::channel(false);
+
+		let Task = tokio::spawn(Fn(PathBuf::from(&Option.entry[0][0]), Option, Shutdown));
+
+		sleep(Duration::from_millis(200)).await;
+
+		(Switch, Task)
+	}
+
+	/// Whether `Condition` holds within five seconds.
+	async fn Until(Condition:impl Fn() -> bool) -> bool {
+		for _ in 0..250 {
+			if Condition() {
+				return true;
+			}
+
+			sleep(Duration::from_millis(20)).await;
+		}
+
+		false
+	}
+
+	#[tokio::test]
+	async fn BurstCompilesOnce() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join("a.ts");
+
+		let Metrics = Arc::new(Mutex::new(CompilerMetrics::default()));
+
+		let Option =
+			Option { metrics:Some(Arc::clone(&Metrics)), ..Fixture::Project(Directory.path()) };
+
+		let (Switch, Task) = Start(Option).await;
+
+		for Value in 0..3 {
+			std::fs::write(&File, format!("export const a = {};", Value)).unwrap();
+
+			sleep(Duration::from_millis(15)).await;
+		}
+
+		assert!(Until(|| File.with_extension("js").exists()).await);
+
+		sleep(Duration::from_millis(500)).await;
+
+		assert_eq!(Metrics.lock().await.Count, 1);
+
+		assert!(std::fs::read_to_string(File.with_extension("js")).unwrap().contains("a = 2;"));
+
+		Switch.send(true).unwrap();
+
+		Task.await.unwrap().unwrap();
+	}
+
+	#[tokio::test]
+	async fn CreateCompilesAndRemoveCleansUp() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join("a.ts");
+
+		let (Switch, Task) = Start(Fixture::Project(Directory.path())).await;
+
+		std::fs::write(&File, "export const a = 1;").unwrap();
+
+		assert!(Until(|| File.with_extension("js").exists()).await);
+
+		std::fs::remove_file(&File).unwrap();
+
+		assert!(Until(|| !File.with_extension("js").exists()).await);
+
+		std::fs::write(Directory.path().join("b.ts"), "export const b = 1;").unwrap();
+
+		assert!(Until(|| Directory.path().join("b.js").exists()).await);
+
+		std::fs::rename(Directory.path().join("b.ts"), Directory.path().join("c.ts")).unwrap();
+
+		assert!(Until(|| {
+			Directory.path().join("c.js").exists() && !Directory.path().join("b.js").exists()
+		})
+		.await);
+
+		Switch.send(true).unwrap();
+
+		Task.await.unwrap().unwrap();
+	}
+
+	#[tokio::test]
+	async fn ConfigEditRecompiles() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let (Source, Config) = (Directory.path().join("src"), Directory.path().join("custom.json"));
+
+		let File = Source.join("a.ts");
+
+		std::fs::create_dir(&Source).unwrap();
+
+		std::fs::write(&File, "export const a = 1;").unwrap();
+
+		std::fs::write(&Config, r#"{ "Module": "commonjs" }"#).unwrap();
+
+		let (Switch, Task) =
+			Start(Option { config_path:Some(Config.clone()), ..Fixture::Project(&Source) }).await;
+
+		let Output = || std::fs::read_to_string(File.with_extension("js")).unwrap_or_default();
+
+		std::fs::write(&Config, r#"{ "Module": "es6" }"#).unwrap();
+
+		assert!(Until(|| Output().contains("export const a = 1;")).await, "{}", Output());
+
+		// A broken config is reported and the last good one kept.
+		std::fs::write(&Config, "{").unwrap();
+
+		sleep(Duration::from_millis(300)).await;
+
+		std::fs::write(&File, "export const a = 2;").unwrap();
+
+		assert!(Until(|| Output().contains("export const a = 2;")).await, "{}", Output());
+
+		Switch.send(true).unwrap();
+
+		Task.await.unwrap().unwrap();
+	}
+
+	#[tokio::test]
+	async fn ShutdownReturnsPromptly() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let (Switch, Task) = Start(Fixture::Project(Directory.path())).await;
+
+		std::fs::write(Directory.path().join("a.ts"), "export const a = 1;").unwrap();
+
+		assert!(Until(|| Directory.path().join("a.js").exists()).await);
+
+		Switch.send(true).unwrap();
+
+		let Returned = tokio::time::timeout(Duration::from_secs(2), Task).await;
+
+		assert!(matches!(Returned, Ok(Ok(Ok(())))), "{:?}", Returned);
+
+		assert!(Directory.path().join(Cache::PATH).exists());
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn ReportIsFlushedEachInterval() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Report = Directory.path().join("report.json");
+
+		let Metrics = Arc::new(Mutex::new(CompilerMetrics { Count:3, ..Default::default() }));
+
+		let Task = Flush(Arc::clone(&Metrics), Report.clone(), Duration::from_secs(30));
+
+		sleep(Duration::from_secs(29)).await;
+
+		assert!(!Report.exists());
+
+		sleep(Duration::from_secs(2)).await;
+
+		assert!(Until(|| Report.exists()).await);
+
+		Metrics.lock().await.Count = 5;
+
+		sleep(Duration::from_secs(30)).await;
+
+		assert!(Until(|| {
+			std::fs::read_to_string(&Report).is_ok_and(|Content| {
+				Content.contains("\"files_processed\": 5")
+			})
+		})
+		.await);
+
+		Task.abort();
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn SymlinkCycleIsWatchedOnce() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Nested = Directory.path().join("src");
+
+		std::fs::create_dir(&Nested).unwrap();
+
+		std::os::unix::fs::symlink(Directory.path(), Nested.join("loop")).unwrap();
+
+		let (Switch, Task) = Start(Fixture::Project(Directory.path())).await;
+
+		std::fs::write(Nested.join("a.ts"), "export const a = 1;").unwrap();
+
+		assert!(Until(|| Nested.join("a.js").exists()).await);
+
+		Switch.send(true).unwrap();
+
+		let Returned = tokio::time::timeout(Duration::from_secs(2), Task).await;
+
+		assert!(matches!(Returned, Ok(Ok(Ok(())))), "{:?}", Returned);
+	}
+
+	#[tokio::test]
+	async fn RemoveCleansUpNestedOutDir() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Package = Directory.path().join("pkg");
+
+		std::fs::create_dir(&Package).unwrap();
+
+		std::fs::write(
+			Package.join(CompilerConfig::PATH),
+			serde_json::json!({ "OutDir": Package.join("out"), "Root": Package }).to_string(),
+		)
+		.unwrap();
+
+		let File = Package.join("a.ts");
+
+		let Output = Package.join("out/a.js");
+
+		let (Switch, Task) = Start(Fixture::Project(Directory.path())).await;
+
+		std::fs::write(&File, "export const a = 1;").unwrap();
+
+		assert!(Until(|| Output.exists()).await);
+
+		std::fs::remove_file(&File).unwrap();
+
+		assert!(Until(|| !Output.exists()).await);
+
+		Switch.send(true).unwrap();
+
+		Task.await.unwrap().unwrap();
+	}
+
+	use tokio::time::sleep;
+
+	use super::*;
+	use crate::{
+		Fixture,
+		Struct::SWC::{Cache, CompilerConfig},
+	};
+}
@@ -1,82 +1,1271 @@
-#[tracing::instrument(skip(Option))]
-pub async fn Fn(Option:super::Option) -> Result<()> {
+#[tracing::instrument(skip(Option), fields(entries = tracing::field::Empty))]
+pub async fn Fn(Option:super::Option) -> Result<CompilerRun> {
 	let (Allow, mut Mark) = mpsc::unbounded_channel();
 	let Queue = FuturesUnordered::new();
 
-	let Compiler = Arc::new(crate::Struct::SWC::Compiler::new(Option.config.clone()));
+	let mut Abort = Vec::new();
 
-	for file in Option
-		.entry
-		.into_par_iter()
-		.filter_map(|entry| {
-			entry
-				.last()
-				.filter(|last| last.ends_with(&Option.pattern))
-				.map(|_| entry[0..entry.len() - 1].join(&Option.separator.to_string()))
-		})
-		.collect()
-	{
+	let mut Compiler = crate::Struct::SWC::Compiler::new(Option.config.clone());
+
+	Compiler.DryRun = Option.dry_run;
+
+	if let Some(Metrics) = &Option.metrics {
+		Compiler.Outlook = Arc::clone(Metrics);
+	}
+
+	let Compiler = Arc::new(Compiler);
+
+	let Cache = Arc::new(Cache::load(&Option.cache_path).await);
+
+	let Permit = Arc::new(Semaphore::new(Option.concurrency.max(1)));
+
+	let Template = Arc::new(Option.on_compiled.clone().filter(|_| !Option.dry_run));
+
+	// Set by the first failure under `fail_fast`, before its permit is given
+	// up, so queued files are left alone rather than raced by the abort.
+	let Stop = Arc::new(AtomicBool::new(false));
+
+	let Entry = Entries(&Option)?;
+
+	tracing::Span::current().record("entries", Entry.len());
+
+	let Bundled = Option.bundle.as_ref().map(|_| Entry.clone());
+
+	// Files the cache and their output cannot vouch for, with their mtime.
+	let mut Stale = Vec::new();
+
+	for file in Entry {
+		let Modified = fs::metadata(&file).await.and_then(|Metadata| Metadata.modified()).ok();
+
+		if let Some(Modified) = Modified {
+			if !Option.force && Cache.is_fresh(Path::new(&file), Modified) {
+				debug!("Skipped: {}", file);
+				Compiler.Outlook.lock().await.Skipped += 1;
+				continue;
+			}
+
+			// Without a cache entry, such as on the first run with an existing
+			// build, output written after the source and its configs last
+			// changed is trusted.
+			if !Option.force
+				&& !Cache.contains(Path::new(&file))
+				&& Current(&Compiler, &file, Modified, Option.config_path.as_deref()).await
+			{
+				debug!("Up to date: {}", file);
+				Compiler.Outlook.lock().await.Skipped += 1;
+				continue;
+			}
+		}
+
+		Stale.push((file, Modified));
+	}
+
+	let Total = Stale.len();
+
+	for Batch in Stale.chunks(Option.batch_size.max(1)).map(<[_]>::to_vec) {
 		let Allow = Allow.clone();
 
 		let Compiler = Arc::clone(&Compiler);
 
-		Queue.push(tokio::spawn(async move {
-			match fs::read_to_string(&file).await {
-				Ok(input) => {
-					match Compiler.compile_file(&file, input).await {
-						Ok(output) => {
-							if let Err(e) = Allow.send((file.clone(), Ok(output))) {
-								error!("Cannot send compilation result: {}", e);
+		let Cache = Arc::clone(&Cache);
+
+		let Permit = Arc::clone(&Permit);
+
+		let Template = Arc::clone(&Template);
+
+		let Force = Option.force;
+
+		let Retry = Option.retry_reads;
+
+		let Limit = Option.per_file_timeout_secs;
+
+		let Stop = Arc::clone(&Stop);
+
+		let FailFast = Option.fail_fast;
+
+		let Task = tokio::spawn(async move {
+			// Taken again before the next file once a timed out compile walks
+			// off with it.
+			let mut Held = None::<OwnedSemaphorePermit>;
+
+			for (file, Modified) in Batch {
+				if Held.is_none() {
+					Held = Some(Arc::clone(&Permit).acquire_owned().await.expect("Cannot Permit."));
+				}
+
+				if Stop.load(Ordering::SeqCst) {
+					return;
+				}
+
+				match Read(&file, Retry).await {
+					Ok(input) => {
+						let Hash = Cache::hash(&input);
+
+						// Tools such as checkouts touch files without changing them.
+						if !Force && Cache.is_same(Path::new(&file), &Hash) {
+							if let Some(Modified) = Modified.filter(|_| !Compiler.DryRun) {
+								Cache.update(PathBuf::from(&file), Modified, Hash);
 							}
-						},
-						Err(e) => {
-							error!("Compilation error for {}: {}", file, e);
-							if let Err(e) = Allow.send((file.clone(), Err(e))) {
-								error!("Cannot send compilation error: {}", e);
+
+							Compiler.Outlook.lock().await.Skipped += 1;
+
+							if let Err(e) = Allow.send((file.clone(), None)) {
+								error!("Cannot send skip: {}", e);
 							}
-						},
-					}
-				},
-				Err(e) => {
-					error!("Failed to read file {}: {}", file, e);
-					if let Err(e) = Allow.send((file.clone(), Err(e.into()))) {
-						error!("Cannot send file read error: {}", e);
-					}
-				},
+
+							continue;
+						}
+
+						let Compiled = match Limit {
+							0 => Compiler.compile_file(&file, input).await,
+							Limit => {
+								let (Compiler, File) = (Arc::clone(&Compiler), file.clone());
+
+								let Work = move |Claim:Arc<AtomicBool>| {
+									async move {
+										Compiler.compile_file_unless(&File, input, &Claim).await
+									}
+								};
+
+								Bound(&file, Limit, Work, &mut Held).await
+							},
+						};
+
+						match Compiled {
+							Ok(output) => {
+								if let Some(Modified) = Modified.filter(|_| !Compiler.DryRun) {
+									Cache.update(PathBuf::from(&file), Modified, Hash);
+								}
+
+								if let Some(Template) = Template.as_deref() {
+									if !Hook(Template, &file, &output.path).await {
+										Compiler.Outlook.lock().await.Hook += 1;
+									}
+								}
+
+								Compiler.Outlook.lock().await.Warning += output.warnings.len();
+
+								if let Err(e) = Allow.send((file.clone(), Some(Ok(output)))) {
+									error!("Cannot send compilation result: {}", e);
+								}
+							},
+							Err(e) => {
+								Compiler.Outlook.lock().await.Error += 1;
+								Stop.store(FailFast, Ordering::SeqCst);
+								if let Err(e) = Allow.send((file.clone(), Some(Err(e)))) {
+									error!("Cannot send compilation error: {}", e);
+								}
+							},
+						}
+					},
+					Err(e) => {
+						error!("Failed to read file {}: {}", file, e);
+						Compiler.Outlook.lock().await.Error += 1;
+						Stop.store(FailFast, Ordering::SeqCst);
+						let e = CompilerError::Read(file.clone(), e);
+						if let Err(e) = Allow.send((file.clone(), Some(Err(e)))) {
+							error!("Cannot send file read error: {}", e);
+						}
+					},
+				}
 			}
-		}));
+		}.in_current_span());
+
+		Abort.push(Task.abort_handle());
+
+		Queue.push(Task);
 	}
 
+	let Progress = (!Option.quiet && std::io::stdout().is_terminal()).then(|| {
+		ProgressBar::new(Total as u64).with_style(
+			ProgressStyle::with_template("{wide_bar} {pos}/{len} {elapsed}")
+				.expect("Cannot ProgressStyle."),
+		)
+	});
+
 	tokio::spawn(async move {
 		Queue.collect::<Vec<_>>().await;
 		drop(Allow);
 	});
 
 	let mut Count = 0;
-	let mut Error = 0;
+
+	let mut Outcome = Vec::new();
+
+	let mut Stopped = None;
 
 	while let Some((file, result)) = Mark.recv().await {
-		match result {
+		let Some(result) = result else {
+			debug!("Unchanged: {}", file);
+
+			if let Some(Progress) = &Progress {
+				Progress.inc(1);
+			}
+
+			continue;
+		};
+
+		match &result {
 			Ok(output) => {
-				info!("Compiled: {} -> {}", file, output);
+				for Warning in &output.warnings {
+					match &Progress {
+						Some(Progress) => Progress.suspend(|| warn!("{}", Warning)),
+						None => warn!("{}", Warning),
+					}
+				}
+
+				if Progress.is_none() && !Option.quiet {
+					info!("Compiled: {} -> {}", file, output.path);
+				}
+
 				Count += 1;
 			},
 			Err(e) => {
-				warn!("Failed to compile {}: {}", file, e);
-				Error += 1;
+				match &Progress {
+					Some(Progress) => Progress.suspend(|| warn!("Failed to compile {}: {}", file, e)),
+					None => warn!("Failed to compile {}: {}", file, e),
+				}
+
+				if Option.fail_fast {
+					for Abort in &Abort {
+						Abort.abort();
+					}
+
+					Stopped = Some(format!("Stopped at the first failure, {}: {:#}", file, e));
+				}
 			},
 		}
+
+		Outcome.push((file, result.map(|output| output.path).map_err(|e| format!("{:#}", e))));
+
+		if let Some(Progress) = &Progress {
+			Progress.inc(1);
+		}
+
+		if Stopped.is_some() {
+			break;
+		}
 	}
 
-	let Outlook = Compiler.metrics.lock().await;
+	if let Some(Progress) = Progress {
+		match Stopped {
+			Some(_) => Progress.abandon(),
+			None => Progress.finish_and_clear(),
+		}
+	}
+
+	if !Option.dry_run {
+		if let Err(e) = Cache.save(&Option.cache_path).await {
+			error!("Cannot save cache: {}", e);
+		}
+	}
+
+	let Outlook = Compiler.Outlook.lock().await;
 
 	info!(
-		"Compilation complete. Processed {} files in {:?}. {} successful, {} failed.",
-		Outlook.files_processed, Outlook.total_time, Count, Error
+		"Compilation complete. Processed {} files in {:?}. {} successful, {} skipped, {} failed.",
+		Outlook.Count, Outlook.Elapsed, Count, Outlook.Skipped, Outlook.Error
+	);
+
+	Failed(&Outcome);
+
+	let Report = Outlook.report();
+
+	if let Some(Path) = &Option.report_path {
+		fs::write(Path, serde_json::to_vec_pretty(&Report)?).await?;
+	}
+
+	if let Some(Stopped) = &Stopped {
+		error!("{}", Stopped);
+	}
+
+	if let (Some(Target), Some(Source)) = (&Option.bundle, Bundled) {
+		if !Option.dry_run && Stopped.is_none() {
+			Bundle(Target, Source, &Compiler, &Outcome).await?;
+		}
+	}
+
+	Ok(CompilerRun { results:Outcome, report:Report, stopped:Stopped })
+}
+
+/// Lists the files `Fn` considers for `Option`, its entries with directories
+/// expanded, kept when they match a pattern and are not ignored.
+pub fn Entries(Option:&super::Option) -> Result<Vec<String>> {
+	let Ignore = Ignore::new(&Option.ignored(), &Option.roots())?;
+
+	let Pattern = Patterns::new(&Option.patterns)?;
+
+	Ok(Expand(&Option.entry, &Ignore)
+		.into_par_iter()
+		.filter(|file| Pattern.is_match(Path::new(file)) && !Ignore.is_match(Path::new(file)))
+		.collect())
+}
+
+/// Prints the files of `Outcome` that failed, sorted, under an `N files
+/// failed:` heading, each with the first line of its error, so they stand out
+/// from the lines logged while compiling. Colored when standard error is a
+/// terminal.
+fn Failed(Outcome:&[(String, std::result::Result<String, String>)]) {
+	let Color = std::io::stderr().is_terminal();
+
+	if let Err(e) = Listing(&mut std::io::stderr().lock(), Outcome, Color) {
+		error!("Cannot list failures: {}", e);
+	}
+}
+
+/// Does the work of `Failed` on `Output`, colored when `Color` is set.
+fn Listing(
+	Output:&mut impl Write,
+	Outcome:&[(String, std::result::Result<String, String>)],
+	Color:bool,
+) -> std::io::Result<()> {
+	let mut Failure = Outcome
+		.iter()
+		.filter_map(|(file, result)| result.as_ref().err().map(|e| (file, e)))
+		.collect::<Vec<_>>();
+
+	Failure.sort();
+
+	if Failure.is_empty() {
+		return Ok(());
+	}
+
+	let Heading = format!(
+		"{} {} failed:",
+		Failure.len(),
+		if Failure.len() == 1 { "file" } else { "files" }
 	);
 
+	writeln!(Output)?;
+
+	match Color {
+		true => writeln!(Output, "{}", Heading.red().bold())?,
+		false => writeln!(Output, "{}", Heading)?,
+	}
+
+	for (file, e) in Failure {
+		let e = e.lines().next().unwrap_or_default();
+
+		match Color {
+			true => writeln!(Output, "  {} {}", file.bold(), e.dimmed())?,
+			false => writeln!(Output, "  {} {}", file, e)?,
+		}
+	}
+
+	Output.flush()
+}
+
+/// Writes the output of every `Source` but those that failed in `Outcome` to
+/// `Target`, sorted by source path, each under a `// --- <file> ---` line.
+/// Source map comments are dropped, as the maps no longer line up. Files
+/// whose output is missing are left out.
+async fn Bundle(
+	Target:&Path,
+	mut Source:Vec<String>,
+	Compiler:&Compiler,
+	Outcome:&[(String, std::result::Result<String, String>)],
+) -> Result<()> {
+	Source.sort();
+
+	let mut Bundle = String::new();
+
+	for file in Source {
+		if Outcome.iter().any(|(Failed, result)| *Failed == file && result.is_err()) {
+			continue;
+		}
+
+		let Output = match Compiler.Emitted(&file).await {
+			Ok((Output, _)) => Output,
+			Err(e) => {
+				warn!("Cannot locate the output of {} to bundle: {}", file, e);
+				continue;
+			},
+		};
+
+		let Ok(Code) = fs::read_to_string(Output).await else {
+			debug!("No output to bundle: {}", file);
+			continue;
+		};
+
+		Bundle.push_str(&format!("// --- {} ---\n", file));
+
+		for Line in Code.lines().filter(|Line| !Line.starts_with("//# sourceMappingURL=")) {
+			Bundle.push_str(Line);
+			Bundle.push('\n');
+		}
+	}
+
+	if let Some(Parent) = Target.parent().filter(|Parent| !Parent.as_os_str().is_empty()) {
+		fs::create_dir_all(Parent).await?;
+	}
+
+	fs::write(Target, Bundle).await?;
+
+	info!("Bundled into {}", Target.display());
+
 	Ok(())
 }
 
+/// Whether the output `compile_file` would write for `file` was written after
+/// `Modified`, after `Config` and after the `swc_config.json` deciding where
+/// it goes. Configs that cannot be read do not count against it.
+async fn Current(Compiler:&Compiler, file:&str, Modified:SystemTime, Config:Option<&Path>) -> bool {
+	let Changed = |Path:PathBuf| async move {
+		fs::metadata(Path).await.and_then(|Metadata| Metadata.modified()).ok()
+	};
+
+	let Ok((Output, Local)) = Compiler.Emitted(file).await else {
+		return false;
+	};
+
+	let Some(Compiled) = Changed(Output).await else {
+		return false;
+	};
+
+	let mut Newest = Modified;
+
+	for Config in Config.map(Path::to_path_buf).into_iter().chain(Local) {
+		if let Some(Config) = Changed(Config).await {
+			Newest = Newest.max(Config);
+		}
+	}
+
+	Compiled > Newest
+}
+
+/// Runs the compile of `file` by `Work` on the blocking pool, so a transform
+/// that never returns cannot stall the workers, failing with
+/// `CompilerError::Timeout` once `Limit` seconds pass.
+///
+/// `Work` is handed the claim of `Compiler::compile_file_unless`. Whichever of
+/// the two sets it first wins: a compile that claimed its output in time is
+/// waited for, while one that lost writes and counts nothing. A timed out
+/// compile keeps its thread until it returns and takes `Held` with it, so the
+/// timeout is reported at once and the caller takes another permit before
+/// its next file.
+async fn Bound<Work, Compiled>(
+	file:&str,
+	Limit:u64,
+	Work:Work,
+	Held:&mut Option<OwnedSemaphorePermit>,
+) -> std::result::Result<CompilerOutput, CompilerError>
+where
+	Work:FnOnce(Arc<AtomicBool>) -> Compiled + Send + 'static,
+	Compiled:Future<Output = std::result::Result<CompilerOutput, CompilerError>>,
+{
+	let Handle = tokio::runtime::Handle::current();
+
+	let Span = tracing::Span::current();
+
+	let Claim = Arc::new(AtomicBool::new(false));
+
+	let mut Task = tokio::task::spawn_blocking({
+		let Claim = Arc::clone(&Claim);
+
+		move || Handle.block_on(Work(Claim).instrument(Span))
+	});
+
+	let Joined = match timeout(Duration::from_secs(Limit), &mut Task).await {
+		Ok(Joined) => Joined,
+		// Already writing its output, so it is let finish.
+		Err(_) if Claim.swap(true, Ordering::SeqCst) => Task.await,
+		Err(_) => {
+			let Stuck = Held.take();
+
+			tokio::spawn(async move {
+				let _ = Task.await;
+
+				drop(Stuck);
+			});
+
+			return Err(CompilerError::Timeout(file.to_string(), Limit));
+		},
+	};
+
+	Joined.unwrap_or_else(|e| Err(CompilerError::Transform(format!("{}: {}", file, e))))
+}
+
+/// Runs the post-compile `Template` for one file, splitting it on whitespace
+/// and substituting `{input}` and `{output}` in every argument. Returns whether
+/// the command ran and exited successfully.
+async fn Hook(Template:&str, Input:&str, Output:&str) -> bool {
+	let mut Argument = Template
+		.split_whitespace()
+		.map(|Argument| Argument.replace("{input}", Input).replace("{output}", Output));
+
+	let Some(Program) = Argument.next() else {
+		return true;
+	};
+
+	match Command::new(&Program).args(Argument).status().await {
+		Ok(Status) if Status.success() => true,
+		Ok(Status) => {
+			warn!("Hook {} for {} exited with {}", Program, Output, Status);
+			false
+		},
+		Err(e) => {
+			warn!("Cannot run hook {} for {}: {}", Program, Output, e);
+			false
+		},
+	}
+}
+
+/// Reads `file` in up to three attempts, doubling the delay between them, when
+/// `Retry` is set and the error is one an editor mid-write can cause.
+async fn Read(file:&str, Retry:bool) -> std::io::Result<String> {
+	Retried(file, Retry, || fs::read_to_string(file)).await
+}
+
+/// `Read`, with each attempt made by `Attempt`.
+async fn Retried<Attempt, Output>(
+	file:&str,
+	Retry:bool,
+	mut Attempt:Attempt,
+) -> std::io::Result<String>
+where
+	Attempt:FnMut() -> Output,
+	Output:Future<Output = std::io::Result<String>>,
+{
+	let mut Delay = Duration::from_millis(50);
+
+	for _ in 0..2 {
+		match Attempt().await {
+			Err(e)
+				if Retry
+					&& matches!(
+						e.kind(),
+						ErrorKind::Interrupted | ErrorKind::WouldBlock | ErrorKind::NotFound
+					) =>
+			{
+				debug!("Retrying read of {} in {:?}: {}", file, Delay, e);
+
+				sleep(Delay).await;
+
+				Delay *= 2;
+			},
+			Result => return Result,
+		}
+	}
+
+	Attempt().await
+}
+
+/// Assembles each entry from its components and replaces directories with the files
+/// beneath them, without descending into ignored directories.
+fn Expand(Entry:&[Vec<String>], Ignore:&Ignore) -> Vec<String> {
+	Entry
+		.iter()
+		.flat_map(|Entry| {
+			let Entry = Entry.iter().collect::<PathBuf>();
+
+			if Entry.is_dir() {
+				WalkDir::new(&Entry)
+					.follow_links(true)
+					.into_iter()
+					.filter_entry(|Walk| !Ignore.is_match(Walk.path()))
+					.filter_map(|Walk| Walk.ok())
+					.filter(|Walk| Walk.file_type().is_file())
+					.map(|Walk| Walk.path().display().to_string())
+					.collect::<Vec<_>>()
+			} else {
+				vec![Entry.display().to_string()]
+			}
+		})
+		.collect()
+}
+
+use std::{
+	future::Future,
+	io::{ErrorKind, IsTerminal, Write},
+	path::{Path, PathBuf},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	time::{Duration, SystemTime},
+};
+
+use anyhow::Result;
+use futures::stream::{FuturesUnordered, StreamExt};
+use indicatif::{ProgressBar, ProgressStyle};
+use owo_colors::OwoColorize;
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
-use tracing::{error, info, warn};
+use tokio::{
+	fs,
+	process::Command,
+	sync::{mpsc, OwnedSemaphorePermit, Semaphore},
+	time::{sleep, timeout},
+};
+use tracing::{debug, error, info, warn, Instrument};
+use walkdir::WalkDir;
+
+use crate::Struct::SWC::{
+	Cache, Compiler, CompilerError, CompilerOutput, CompilerRun, Ignore, Patterns,
+};
+
+#[cfg(test)]
+mod Test {
+	/// Work that sleeps past a one second `Limit`, claiming its output either
+	/// before or after, and reports whether it would have written it.
+	fn Slow(Early:bool, Wrote:Arc<AtomicBool>) -> impl FnOnce(Arc<AtomicBool>) -> Pin {
+		move |Claim| {
+			Box::pin(async move {
+				let Claimed = || !Claim.swap(true, Ordering::SeqCst);
+
+				let Won = match Early {
+					true => {
+						let Won = Claimed();
+
+						sleep(Duration::from_millis(1500)).await;
+
+						Won
+					},
+					false => {
+						sleep(Duration::from_millis(1500)).await;
+
+						Claimed()
+					},
+				};
+
+				match Won {
+					true => {
+						Wrote.store(true, Ordering::SeqCst);
+
+						Ok(CompilerOutput { path:"a.js".to_string(), warnings:Vec::new() })
+					},
+					false => Err(CompilerError::Transform("a.ts: Abandoned".to_string())),
+				}
+			})
+		}
+	}
+
+	type Pin = std::pin::Pin<
+		Box<dyn Future<Output = std::result::Result<CompilerOutput, CompilerError>> + Send>,
+	>;
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn BoundTimesOutSlowFile() {
+		// A single permit, held by the compile that gets stuck.
+		let Permit = Arc::new(Semaphore::new(1));
+
+		let mut Held = Some(Arc::clone(&Permit).acquire_owned().await.unwrap());
+
+		let Wrote = Arc::new(AtomicBool::new(false));
+
+		let Result = timeout(
+			Duration::from_secs(5),
+			Bound("a.ts", 1, Slow(false, Arc::clone(&Wrote)), &mut Held),
+		)
+		.await
+		.expect("Bound waited for a permit.");
+
+		assert!(matches!(Result, Err(CompilerError::Timeout(File, 1)) if File == "a.ts"));
+
+		// The abandoned compile took the permit with it.
+		assert!(Held.is_none());
+
+		assert_eq!(Permit.available_permits(), 0);
+
+		sleep(Duration::from_secs(1)).await;
+
+		assert!(!Wrote.load(Ordering::SeqCst));
+
+		assert_eq!(Permit.available_permits(), 1);
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn BoundWaitsForClaimedFile() {
+		let Permit = Arc::new(Semaphore::new(1));
+
+		let mut Held = Some(Arc::clone(&Permit).acquire_owned().await.unwrap());
+
+		let Wrote = Arc::new(AtomicBool::new(false));
+
+		let Result = Bound("a.ts", 1, Slow(true, Arc::clone(&Wrote)), &mut Held).await;
+
+		assert_eq!(Result.unwrap().path, "a.js");
+
+		assert!(Wrote.load(Ordering::SeqCst));
+	}
+
+	#[tokio::test]
+	async fn SecondRunHitsCache() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		std::fs::write(Directory.path().join("a.ts"), "export const a = 1;").unwrap();
+
+		let First = Fn(Fixture::Project(Directory.path())).await.unwrap();
+
+		assert_eq!((First.results.len(), First.report.skipped), (1, 0));
+
+		let Second = Fn(Fixture::Project(Directory.path())).await.unwrap();
+
+		assert_eq!((Second.results.len(), Second.report.skipped), (0, 1));
+
+		let Forced = Fn(Option { force:true, ..Fixture::Project(Directory.path()) }).await.unwrap();
+
+		assert_eq!((Forced.results.len(), Forced.report.skipped), (1, 0));
+	}
+
+	#[tokio::test]
+	async fn IgnoredDirectoriesAreSkipped() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		for File in ["a.ts", "node_modules/b.ts", "dist/c.ts"] {
+			let File = Directory.path().join(File);
+
+			std::fs::create_dir_all(File.parent().unwrap()).unwrap();
+
+			std::fs::write(File, "export const a = 1;").unwrap();
+		}
+
+		let mut Option = Fixture::Project(Directory.path());
+
+		Option.ignore.push("dist".to_string());
+
+		let Run = Fn(Option).await.unwrap();
+
+		assert_eq!(Run.results.len(), 1);
+
+		assert!(Directory.path().join("a.js").exists());
+
+		assert!(!Directory.path().join("node_modules/b.js").exists());
+
+		assert!(!Directory.path().join("dist/c.js").exists());
+	}
+
+	#[cfg(unix)]
+	#[tokio::test(flavor = "multi_thread")]
+	async fn ConcurrencyIsCapped() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		for Index in 0..8 {
+			std::fs::write(Directory.path().join(format!("{}.ts", Index)), "export {};").unwrap();
+		}
+
+		// Hooks run while their file holds its permit, so they overlap no more
+		// than the compiles do.
+		let Script = Directory.path().join("hook.sh");
+
+		std::fs::write(
+			&Script,
+			"D=$(dirname \"$1\")/running\nmkdir -p \"$D\"\ntouch \"$D/$$\"\nls \"$D\" | wc -l \
+			 >> \"$D/../counts\"\nsleep 0.1\nrm \"$D/$$\"\n",
+		)
+		.unwrap();
+
+		let Run = Fn(Option {
+			concurrency:2,
+			on_compiled:Some(format!("sh {} {{output}}", Script.display())),
+			..Fixture::Project(Directory.path())
+		})
+		.await
+		.unwrap();
+
+		assert_eq!(Run.results.len(), 8);
+
+		let Count = std::fs::read_to_string(Directory.path().join("counts")).unwrap();
+
+		let Count =
+			Count.lines().map(|Line| Line.trim().parse::<usize>().unwrap()).collect::<Vec<_>>();
+
+		assert_eq!(Count.len(), 8);
+
+		assert!(Count.iter().all(|Count| (1..=2).contains(Count)), "{:?}", Count);
+	}
+
+	#[tokio::test]
+	async fn BrokenFileCountsAsError() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		std::fs::write(Directory.path().join("a.ts"), "export const a = 1;").unwrap();
+
+		std::fs::write(Directory.path().join("b.ts"), "const x = ;").unwrap();
+
+		let Metrics = Arc::new(Mutex::new(CompilerMetrics::default()));
+
+		let Run =
+			Fn(Option { metrics:Some(Arc::clone(&Metrics)), ..Fixture::Project(Directory.path()) })
+				.await
+				.unwrap();
+
+		assert_eq!(Metrics.lock().await.Error, 1);
+
+		assert_eq!(Run.report.errors, 1);
+
+		assert_eq!(Run.results.iter().filter(|(_, Result)| Result.is_err()).count(), 1);
+	}
+
+	#[tokio::test]
+	async fn ReportCountsFiles() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		for File in ["a.ts", "b.ts", "c.tsx"] {
+			std::fs::write(Directory.path().join(File), "export const a = 1;").unwrap();
+		}
+
+		let Report = Directory.path().join("report.json");
+
+		Fn(Option { report_path:Some(Report.clone()), ..Fixture::Project(Directory.path()) })
+			.await
+			.unwrap();
+
+		let Report =
+			serde_json::from_slice::<serde_json::Value>(&std::fs::read(Report).unwrap()).unwrap();
+
+		assert_eq!(Report["files_processed"], 3);
+
+		assert_eq!(Report["errors"], 0);
+
+		assert_eq!(Report["slowest"].as_array().unwrap().len(), 3);
+
+		assert!(Report["total_ms"].is_u64());
+	}
+
+	#[tokio::test]
+	async fn DryRunWritesNothing() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		for File in ["a.ts", "b.ts"] {
+			std::fs::write(Directory.path().join(File), "export const a = 1;").unwrap();
+		}
+
+		let Run = Fn(Option { dry_run:true, ..Fixture::Project(Directory.path()) }).await.unwrap();
+
+		assert_eq!(Run.report.files_processed, 2);
+
+		assert!(Run
+			.results
+			.iter()
+			.all(|(_, Result)| Result.as_ref().is_ok_and(|Path| Path.starts_with("(dry-run) "))));
+
+		assert!(!Directory.path().join("a.js").exists() && !Directory.path().join("b.js").exists());
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn HookReceivesPaths() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		std::fs::write(Directory.path().join("a.ts"), "export const a = 1;").unwrap();
+
+		let (Script, Record) = (Directory.path().join("hook.sh"), Directory.path().join("record"));
+
+		std::fs::write(&Script, format!("echo \"$@\" > {}\n", Record.display())).unwrap();
+
+		let Run = Fn(Option {
+			on_compiled:Some(format!("sh {} {{input}} {{output}}", Script.display())),
+			..Fixture::Project(Directory.path())
+		})
+		.await
+		.unwrap();
+
+		assert_eq!(Run.report.hooks_failed, 0);
+
+		let (Input, Output) = (Directory.path().join("a.ts"), Directory.path().join("a.js"));
+
+		assert_eq!(
+			std::fs::read_to_string(Record).unwrap().trim(),
+			format!("{} {}", Input.display(), Output.display())
+		);
+
+		assert!(Hook("echo {output}", "a.ts", "a.js").await);
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn FailedHookIsCounted() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		std::fs::write(Directory.path().join("a.ts"), "export const a = 1;").unwrap();
+
+		let Run = Fn(Option {
+			on_compiled:Some("false".to_string()),
+			..Fixture::Project(Directory.path())
+		})
+		.await
+		.unwrap();
+
+		assert_eq!(Run.report.hooks_failed, 1);
+
+		assert!(Run.results.iter().all(|(_, Result)| Result.is_ok()));
+
+		assert!(Directory.path().join("a.js").exists());
+	}
+
+	#[tokio::test]
+	async fn EveryInputCompletes() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		for Index in 0..4 {
+			std::fs::write(Directory.path().join(format!("{}.ts", Index)), "export {};").unwrap();
+		}
+
+		std::fs::write(Directory.path().join("broken.ts"), "const x = ;").unwrap();
+
+		let First = Fn(Fixture::Project(Directory.path())).await.unwrap();
+
+		assert_eq!(First.results.len() + First.report.skipped, 5);
+
+		// Unchanged files complete as skipped rather than not at all.
+		let Second = Fn(Fixture::Project(Directory.path())).await.unwrap();
+
+		assert_eq!((Second.results.len(), Second.report.skipped), (1, 4));
+	}
+
+	#[tokio::test]
+	async fn ResultsNameEachFile() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let (Good, Broken) = (Directory.path().join("good.ts"), Directory.path().join("broken.ts"));
+
+		std::fs::write(&Good, "export const a = 1;").unwrap();
+
+		std::fs::write(&Broken, "const x = ;").unwrap();
+
+		let mut Run = Fn(Fixture::Project(Directory.path())).await.unwrap();
+
+		Run.results.sort();
+
+		let [(First, Failed), (Second, Compiled)] = Run.results.as_slice() else {
+			panic!("{:?}", Run.results);
+		};
+
+		assert_eq!(First.as_str(), Broken.to_string_lossy());
+
+		assert_eq!(Second.as_str(), Good.to_string_lossy());
+
+		assert!(Failed.as_ref().unwrap_err().contains("broken.ts"));
+
+		assert_eq!(Compiled.as_deref(), Ok(&*Good.with_extension("js").to_string_lossy()));
+	}
+
+	#[tokio::test]
+	async fn IdenticalRewriteIsSkipped() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join("a.ts");
+
+		std::fs::write(&File, "export const a = 1;").unwrap();
+
+		Fn(Fixture::Project(Directory.path())).await.unwrap();
+
+		// Rewritten as a checkout would, with the same content and a later mtime.
+		let Later = std::fs::metadata(&File).unwrap().modified().unwrap() + Duration::from_secs(60);
+
+		std::fs::write(&File, "export const a = 1;").unwrap();
+
+		std::fs::File::options().write(true).open(&File).unwrap().set_modified(Later).unwrap();
+
+		let Touched = Fn(Fixture::Project(Directory.path())).await.unwrap();
+
+		assert_eq!((Touched.results.len(), Touched.report.skipped), (0, 1));
+
+		std::fs::write(&File, "export const a = 2;").unwrap();
+
+		let Changed = Fn(Fixture::Project(Directory.path())).await.unwrap();
+
+		assert_eq!((Changed.results.len(), Changed.report.skipped), (1, 0));
+	}
+
+	#[tokio::test]
+	async fn EveryPatternIsCompiled() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		for File in ["a.ts", "b.tsx", "c.mts", "types.d.ts"] {
+			std::fs::write(Directory.path().join(File), "export const a = 1;").unwrap();
+		}
+
+		let Option = Option {
+			patterns:[".ts", ".tsx", ".mts"].map(str::to_string).to_vec(),
+			..Fixture::Project(Directory.path())
+		};
+
+		let Run = Fn(Option).await.unwrap();
+
+		assert_eq!(Run.results.len(), 3);
+
+		for Output in ["a.js", "b.js", "c.js"] {
+			assert!(Directory.path().join(Output).exists(), "{} missing", Output);
+		}
+
+		assert!(!Directory.path().join("types.d.js").exists());
+	}
+
+	#[tokio::test]
+	async fn ReadRetriesTransientErrors() {
+		let Failure = [ErrorKind::NotFound, ErrorKind::Interrupted];
+
+		let Attempt = || {
+			let mut Count = 0;
+
+			move || {
+				Count += 1;
+
+				let Result = match Failure.get(Count - 1) {
+					Some(Kind) => Err(std::io::Error::from(*Kind)),
+					None => Ok("export {};".to_string()),
+				};
+
+				async move { Result }
+			}
+		};
+
+		assert_eq!(Retried("a.ts", true, Attempt()).await.unwrap(), "export {};");
+
+		let Failed = Retried("a.ts", false, Attempt()).await.unwrap_err();
+
+		assert_eq!(Failed.kind(), ErrorKind::NotFound);
+	}
+
+	#[tokio::test]
+	async fn TestFilesAreExcluded() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		for File in ["foo.ts", "foo.test.ts", "foo.spec.ts", "__tests__/bar.ts", "foo.check.ts"] {
+			let File = Directory.path().join(File);
+
+			std::fs::create_dir_all(File.parent().unwrap()).unwrap();
+
+			std::fs::write(File, "export const a = 1;").unwrap();
+		}
+
+		Fn(Fixture::Project(Directory.path())).await.unwrap();
+
+		assert!(Directory.path().join("foo.test.js").exists());
+
+		for File in ["foo.js", "foo.test.js", "foo.spec.js", "__tests__/bar.js", "foo.check.js"] {
+			std::fs::remove_file(Directory.path().join(File)).unwrap();
+		}
+
+		let mut Option = Fixture::Project(Directory.path());
+
+		Option.force = true;
+
+		Option.exclude_tests = true;
+
+		Option.test_patterns.push("*.check.ts".to_string());
+
+		let Run = Fn(Option).await.unwrap();
+
+		assert_eq!(Run.results.len(), 1);
+
+		assert!(Directory.path().join("foo.js").exists());
+
+		for File in ["foo.test.js", "foo.spec.js", "__tests__/bar.js", "foo.check.js"] {
+			assert!(!Directory.path().join(File).exists(), "{}", File);
+		}
+	}
+
+	#[tokio::test]
+	async fn FailFastStopsAtFirstError() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		for File in ["a.ts", "b.ts"] {
+			std::fs::write(Directory.path().join(File), "const x = ;").unwrap();
+		}
+
+		let Metrics = Arc::new(Mutex::new(CompilerMetrics::default()));
+
+		let Option = Option {
+			metrics:Some(Arc::clone(&Metrics)),
+			concurrency:1,
+			fail_fast:true,
+			..Fixture::Project(Directory.path())
+		};
+
+		let Run = Fn(Option).await.unwrap();
+
+		let Stopped = Run.stopped.unwrap();
+
+		assert!(Stopped.starts_with("Stopped at the first failure"), "{}", Stopped);
+
+		assert_eq!(Run.results.len(), 1);
+
+		assert_eq!(Run.report.errors, 1);
+
+		assert_eq!(Metrics.lock().await.Error, 1);
+	}
+
+	#[tokio::test]
+	async fn UnchangedFileIsCountedSkipped() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let (Changed, Unchanged) = (Directory.path().join("a.ts"), Directory.path().join("b.ts"));
+
+		for File in [&Changed, &Unchanged] {
+			std::fs::write(File, "export const a = 1;").unwrap();
+		}
+
+		Fn(Fixture::Project(Directory.path())).await.unwrap();
+
+		let Later =
+			std::fs::metadata(&Changed).unwrap().modified().unwrap() + Duration::from_secs(60);
+
+		std::fs::write(&Changed, "export const a = 2;").unwrap();
+
+		std::fs::File::options().write(true).open(&Changed).unwrap().set_modified(Later).unwrap();
+
+		let Metrics = Arc::new(Mutex::new(CompilerMetrics::default()));
+
+		let Run =
+			Fn(Option { metrics:Some(Arc::clone(&Metrics)), ..Fixture::Project(Directory.path()) })
+				.await
+				.unwrap();
+
+		assert_eq!(Run.report.skipped, 1);
+
+		assert_eq!(Metrics.lock().await.Skipped, 1);
+
+		assert_eq!(Run.results.len(), 1);
+
+		assert_eq!(Run.results[0].0, Changed.to_string_lossy());
+	}
+
+	#[tokio::test]
+	async fn FailuresAreListedTogether() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Source = [("a.ts", "export const a = 1;"), ("b.ts", "const x = ;"), ("c.ts", "}")];
+
+		for (File, Source) in Source {
+			std::fs::write(Directory.path().join(File), Source).unwrap();
+		}
+
+		let Run = Fn(Fixture::Project(Directory.path())).await.unwrap();
+
+		let mut Output = Vec::new();
+
+		Listing(&mut Output, &Run.results, false).unwrap();
+
+		let Output = String::from_utf8(Output).unwrap();
+
+		let Line = Output.lines().collect::<Vec<_>>();
+
+		assert_eq!(Line.len(), 4, "{}", Output);
+
+		assert_eq!(Line[1], "2 files failed:");
+
+		for (Line, File) in Line[2..].iter().zip(["b.ts", "c.ts"]) {
+			let File = Directory.path().join(File).to_string_lossy().to_string();
+
+			assert!(Line.starts_with(&format!("  {} ", File)), "{}", Line);
+		}
+
+		assert!(!Output.contains("a.ts"), "{}", Output);
+	}
+
+	#[tokio::test]
+	async fn BatchingKeepsOutputs() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let mut Output = Vec::new();
+
+		for Batch in [1, 7] {
+			let Root = Directory.path().join(Batch.to_string());
+
+			std::fs::create_dir(&Root).unwrap();
+
+			for Index in 0..20 {
+				std::fs::write(
+					Root.join(format!("{}.ts", Index)),
+					format!("export const a{}: number = {};", Index, Index),
+				)
+				.unwrap();
+			}
+
+			let Metrics = Arc::new(Mutex::new(CompilerMetrics::default()));
+
+			let Option = Option {
+				batch_size:Batch,
+				metrics:Some(Arc::clone(&Metrics)),
+				..Fixture::Project(&Root)
+			};
+
+			assert_eq!(Fn(Option).await.unwrap().results.len(), 20);
+
+			assert_eq!(Metrics.lock().await.File.len(), 20);
+
+			let Read = |Index:usize| std::fs::read_to_string(Root.join(format!("{}.js", Index)));
+
+			Output.push((0..20).map(|Index| Read(Index).unwrap()).collect::<Vec<_>>());
+		}
+
+		assert_eq!(Output[0], Output[1]);
+	}
+
+	#[tokio::test]
+	async fn BundleHoldsEveryFile() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		for (File, Source) in
+			[("b.ts", "export const b = 2;"), ("a.ts", "export const a = 1;"), ("c.ts", "}")]
+		{
+			std::fs::write(Directory.path().join(File), Source).unwrap();
+		}
+
+		let Target = Directory.path().join("out/bundle.js");
+
+		let Option = Option { bundle:Some(Target.clone()), ..Fixture::Project(Directory.path()) };
+
+		Fn(Option.clone()).await.unwrap();
+
+		let Bundle = std::fs::read_to_string(&Target).unwrap();
+
+		let Separator = |File:&str| format!("// --- {} ---", Directory.path().join(File).display());
+
+		let (A, B) = (Bundle.find(&Separator("a.ts")), Bundle.find(&Separator("b.ts")));
+
+		assert!(A.is_some() && A < B, "{}", Bundle);
+
+		assert!(Bundle.contains("const a = 1;") && Bundle.contains("const b = 2;"), "{}", Bundle);
+
+		assert!(!Bundle.contains("c.ts"), "{}", Bundle);
+
+		// Cached files are bundled from the output they left behind.
+		std::fs::remove_file(&Target).unwrap();
+
+		Fn(Option).await.unwrap();
+
+		assert_eq!(std::fs::read_to_string(&Target).unwrap(), Bundle);
+	}
+
+	#[tokio::test]
+	async fn BundleFindsNestedOutDir() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Package = Directory.path().join("pkg");
+
+		std::fs::create_dir(&Package).unwrap();
+
+		std::fs::write(
+			Package.join(CompilerConfig::PATH),
+			serde_json::json!({ "OutDir": Package.join("out"), "Root": Package }).to_string(),
+		)
+		.unwrap();
+
+		std::fs::write(Directory.path().join("a.ts"), "export const a = 1;").unwrap();
+
+		std::fs::write(Package.join("b.ts"), "export const b = 2;").unwrap();
+
+		let Target = Directory.path().join("bundle.js");
+
+		Fn(Option { bundle:Some(Target.clone()), ..Fixture::Project(Directory.path()) })
+			.await
+			.unwrap();
+
+		assert!(Package.join("out/b.js").exists());
+
+		let Bundle = std::fs::read_to_string(&Target).unwrap();
+
+		assert!(Bundle.contains("const a = 1;") && Bundle.contains("const b = 2;"), "{}", Bundle);
+	}
+
+	use tokio::sync::Mutex;
+
+	use super::*;
+	use crate::{
+		Fixture,
+		Struct::SWC::{CompilerConfig, CompilerMetrics, Option},
+	};
+}
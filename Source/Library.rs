@@ -1,4 +1,5 @@
 #![allow(non_snake_case)]
+#![allow(clippy::tabs_in_doc_comments)]
 
 #[allow(dead_code)]
 #[tokio::main]
@@ -15,11 +16,15 @@
 ///
 /// # Example
 ///
-/// ```rust
+/// ```rust,no_run
+/// # use Library::Struct;
 /// #[tokio::main]
 /// async fn main() { (Struct::Binary::Command::Struct::Fn().Fn)().await }
 /// ```
 async fn main() { (Struct::Binary::Command::Struct::Fn().Fn)().await }
 
+#[cfg(test)]
+mod Fixture;
+
 pub mod Fn;
 pub mod Struct;
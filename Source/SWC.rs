@@ -0,0 +1,12 @@
+#![allow(non_snake_case)]
+
+/// The entry point of `RestSWC`, which compiles the TypeScript beneath a
+/// directory, and keeps recompiling it on changes with `--Watch`.
+///
+/// # Errors
+///
+/// This function will return an error if standard input or output cannot be
+/// used, or the watcher cannot start. Failed compilations exit with `1`
+/// instead.
+#[tokio::main]
+async fn main() -> anyhow::Result<()> { Library::Fn::SWC::Fn().await }
@@ -2,7 +2,7 @@
 ///
 /// This struct holds various fields related to the command entries, including
 /// the entry paths, parallel execution flag, pattern to match, separator for
-/// file paths, and omit patterns.
+/// file paths, omit patterns, and parallel concurrency limit.
 pub struct Struct {
 	/// A vector of vectors, where each inner vector contains the components of
 	/// a file path.
@@ -16,6 +16,43 @@ pub struct Struct {
 
 	/// The separator used for file paths.
 	pub Separator:Separator,
+
+	/// The maximum number of entries processed at the same time in parallel.
+	pub Concurrency:Concurrency,
+
+	/// The path a Markdown changelog is written to, if any.
+	pub Changelog:Changelog,
+
+	/// The prefix commit ids are appended to when linking them in the
+	/// changelog.
+	pub Url:Url,
+
+	/// A flag indicating whether to collect change stats instead of patches.
+	pub Stat:Stat,
+
+	/// A flag indicating whether to collapse commits sharing a summary.
+	pub Dedup:Dedup,
+
+	/// The output format, `text` or `jsonl`.
+	pub Format:Format,
+
+	/// A flag indicating whether to print the per-author leaderboard.
+	pub Leaderboard:Leaderboard,
+
+	/// A flag indicating whether to collect only the commits since the last
+	/// tag.
+	pub SinceTag:SinceTag,
+
+	/// The number of characters summaries are cut to in the report, if any.
+	pub Truncate:Truncate,
+
+	/// A flag indicating whether to print the full commit message in the
+	/// report.
+	pub Body:Body,
+
+	/// A flag indicating whether to print the report, the JSON lines and the
+	/// leaderboard instead of only returning the results.
+	pub Print:Print,
 }
 
 impl Struct {
@@ -39,11 +76,25 @@ impl Struct {
 			Parallel:Option.Parallel,
 			Pattern:Option.Pattern.clone(),
 			Separator:Option.Separator,
+			Concurrency:Option.Concurrency,
+			Changelog:Option.Changelog.clone(),
+			Url:Option.Url.clone(),
+			Stat:Option.Stat,
+			Dedup:Option.Dedup,
+			Format:Option.Format.clone(),
+			Leaderboard:Option.Leaderboard,
+			SinceTag:Option.SinceTag,
+			Truncate:Option.Truncate,
+			Body:Option.Body,
+			Print:true,
 		}
 	}
 }
 
-use crate::Struct::Binary::Command::Option::{Parallel, Pattern, Separator, Struct as Option};
+use crate::Struct::Binary::Command::Option::{
+	Body, Changelog, Concurrency, Dedup, Format, Leaderboard, Parallel, Pattern, Print, Separator,
+	SinceTag, Stat, Struct as Option, Truncate, Url,
+};
 
 /// Type alias for a vector of vectors, where each inner vector contains the
 /// components of a file path.
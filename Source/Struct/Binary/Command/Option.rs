@@ -2,7 +2,7 @@
 ///
 /// This struct holds various fields related to the command options, including
 /// exclude patterns, omit patterns, parallel execution flag, pattern to match,
-/// root directory, and separator for file paths.
+/// root directory, separator for file paths, and parallel concurrency limit.
 pub struct Struct {
 	/// A vector of strings representing patterns to exclude.
 	pub Exclude:Vec<String>,
@@ -18,6 +18,39 @@ pub struct Struct {
 
 	/// The separator used for file paths.
 	pub Separator:Separator,
+
+	/// The maximum number of entries processed at the same time in parallel.
+	pub Concurrency:Concurrency,
+
+	/// The path a Markdown changelog is written to, if any.
+	pub Changelog:Changelog,
+
+	/// The prefix commit ids are appended to when linking them in the
+	/// changelog.
+	pub Url:Url,
+
+	/// A flag indicating whether to collect change stats instead of patches.
+	pub Stat:Stat,
+
+	/// A flag indicating whether to collapse commits sharing a summary.
+	pub Dedup:Dedup,
+
+	/// The output format, `text` or `jsonl`.
+	pub Format:Format,
+
+	/// A flag indicating whether to print the per-author leaderboard.
+	pub Leaderboard:Leaderboard,
+
+	/// A flag indicating whether to collect only the commits since the last
+	/// tag.
+	pub SinceTag:SinceTag,
+
+	/// The number of characters summaries are cut to in the report, if any.
+	pub Truncate:Truncate,
+
+	/// A flag indicating whether to print the full commit message in the
+	/// report.
+	pub Body:Body,
 }
 
 impl Struct {
@@ -47,6 +80,16 @@ impl Struct {
 			Pattern:Command().get_one::<String>("Pattern").expect("Cannot Pattern.").to_owned(),
 			Root:Command().get_one::<String>("Root").expect("Cannot Root.").to_owned(),
 			Separator,
+			Concurrency:Command().get_one::<usize>("Concurrency").copied().unwrap_or_else(num_cpus::get),
+			Changelog:Command().get_one::<String>("Changelog").cloned(),
+			Url:Command().get_one::<String>("Url").cloned(),
+			Stat:Command().get_flag("Stat"),
+			Dedup:Command().get_flag("Dedup"),
+			Format:Command().get_one::<String>("Format").expect("Cannot Format.").to_owned(),
+			Leaderboard:Command().get_flag("Leaderboard"),
+			SinceTag:Command().get_flag("SinceTag"),
+			Truncate:Command().get_one::<usize>("Truncate").copied(),
+			Body:Command().get_flag("Body"),
 		}
 	}
 }
@@ -64,3 +107,36 @@ pub type Pattern = String;
 
 /// Type alias for a character used as a separator for file paths.
 pub type Separator = char;
+
+/// Type alias for the number of entries processed at the same time.
+pub type Concurrency = usize;
+
+/// Type alias for an optional path the changelog is written to.
+pub type Changelog = std::option::Option<String>;
+
+/// Type alias for an optional prefix commit links are built from.
+pub type Url = std::option::Option<String>;
+
+/// Type alias for a boolean flag selecting change stats over patches.
+pub type Stat = bool;
+
+/// Type alias for a boolean flag collapsing commits sharing a summary.
+pub type Dedup = bool;
+
+/// Type alias for the name of the output format.
+pub type Format = String;
+
+/// Type alias for a boolean flag printing the per-author leaderboard.
+pub type Leaderboard = bool;
+
+/// Type alias for a boolean flag stopping the walk at the last tag.
+pub type SinceTag = bool;
+
+/// Type alias for an optional number of characters summaries are cut to.
+pub type Truncate = std::option::Option<usize>;
+
+/// Type alias for a boolean flag printing full commit messages.
+pub type Body = bool;
+
+/// Type alias for a boolean flag writing the results to standard output.
+pub type Print = bool;
@@ -0,0 +1,169 @@
+/// Represents the options narrowing which commits `Build::Fn` collects.
+#[derive(Debug, Clone, Default)]
+pub struct Struct {
+	/// Skip commits made before this instant, inclusive of the instant itself.
+	pub Since:Option<DateTime<FixedOffset>>,
+
+	/// Skip commits made after this instant, inclusive of the instant itself.
+	pub Until:Option<DateTime<FixedOffset>>,
+
+	/// Which of the dates of a commit `Since` and `Until` are compared with.
+	pub Date:Date,
+
+	/// How each collected commit is keyed.
+	pub Key:Key,
+
+	/// Whether merge commits, those with more than one parent, are collected.
+	pub Merge:Merge,
+
+	/// Stop walking once this many commits have passed the other filters.
+	pub Limit:Option<usize>,
+
+	/// Branch, tag or revspec to walk from instead of `HEAD`.
+	pub Rev:Option<String>,
+
+	/// Keep only commits touching a file beneath one of these prefixes,
+	/// relative to the repository root. Empty keeps every commit.
+	pub Path:Vec<PathBuf>,
+
+	/// Store a `3 files, +40 -12` line after each summary instead of the
+	/// full patch.
+	pub Stat:bool,
+
+	/// Collapse commits sharing a summary into the most recent of them, with
+	/// the summary suffixed by how many there were, such as `message (x3)`.
+	pub Dedup:bool,
+
+	/// Stop at the most recent tag reachable from where the walk starts, so
+	/// only the commits since the last release are collected. Without tags the
+	/// whole history is walked.
+	pub SinceTag:bool,
+
+	/// Cap the threads diffing commits at once, `None` sharing rayon's global
+	/// pool.
+	pub Threads:Option<usize>,
+
+	/// Discovery results shared with other `Build::Fn` calls, so entries
+	/// already seen reopen their repository without searching for it again.
+	pub Cache:Option<Arc<Cache>>,
+}
+
+/// Represents the repository roots discovered across `Build::Fn` calls.
+///
+/// Only paths are shared, as `Repository` itself cannot be shared between
+/// threads; each call still opens its own handle.
+#[derive(Debug, Default)]
+pub struct Cache {
+	/// The repository root discovered for each entry.
+	pub Root:DashMap<PathBuf, PathBuf>,
+
+	/// How many discoveries were skipped because the entry was already known.
+	pub Hit:AtomicUsize,
+}
+
+/// Represents the strategy used to key the commits `Build::Fn` collects.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Key {
+	/// Key by the sequential walk index, storing the short id. Keys shift
+	/// whenever history changes.
+	#[default]
+	Index,
+
+	/// Key by the first eight bytes of the commit `Oid`, storing the full id.
+	/// Keys stay the same across runs, so caches of summaries built from them
+	/// remain valid as history grows.
+	Oid,
+}
+
+/// Represents which date of a commit `Build::Fn` filters on. The two differ
+/// once a commit has been rebased, amended or cherry-picked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Date {
+	/// When the change was first written.
+	Author,
+
+	/// When the commit was last written, the date `git log` orders by.
+	#[default]
+	Committer,
+}
+
+/// Represents how `Build::Fn` treats merge commits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Merge {
+	/// Collect merge commits alongside the others.
+	Include,
+
+	/// Skip merge commits, whose summaries rarely say more than which branch
+	/// was merged.
+	#[default]
+	Exclude,
+
+	/// Collect merge commits alone.
+	Only,
+}
+
+/// Represents the ways `Build::Fn` can fail.
+#[derive(Debug)]
+pub enum Error {
+	/// The entry is not inside a git repository, so there is nothing to walk.
+	NotARepository(PathBuf),
+
+	/// The revision to walk from does not resolve to a commit.
+	Rev(String, git2::Error),
+
+	/// Any other failure reading the repository.
+	Git(git2::Error),
+
+	/// The thread pool diffing commits could not be started.
+	Pool(String),
+
+	/// The blocking task walking the repository panicked or was cancelled.
+	Task(String),
+}
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, Formatter:&mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::NotARepository(Path) => write!(Formatter, "{} is not a repository", Path.display()),
+			Self::Rev(Rev, _Error) => write!(Formatter, "Cannot resolve {}: {}", Rev, _Error),
+			Self::Git(_Error) => write!(Formatter, "{}", _Error),
+			Self::Pool(_Error) => write!(Formatter, "Cannot start the diff pool: {}", _Error),
+			Self::Task(_Error) => write!(Formatter, "Cannot finish the walk: {}", _Error),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::NotARepository(_) | Self::Pool(_) | Self::Task(_) => None,
+			Self::Rev(_, _Error) | Self::Git(_Error) => Some(_Error),
+		}
+	}
+}
+
+impl From<git2::Error> for Error {
+	fn from(_Error:git2::Error) -> Self { Self::Git(_Error) }
+}
+
+impl Error {
+	/// Classifies a failure to open or discover the repository at `Path`.
+	pub fn open(Path:&std::path::Path, _Error:git2::Error) -> Self {
+		match _Error.code() {
+			git2::ErrorCode::NotFound => Self::NotARepository(Path.to_path_buf()),
+			_ => Self::Git(_Error),
+		}
+	}
+}
+
+use std::{
+	path::PathBuf,
+	sync::{atomic::AtomicUsize, Arc},
+};
+
+use chrono::{DateTime, FixedOffset};
+use dashmap::DashMap;
+
+/// Type alias for the commits collected from one repository, mapping the key
+/// chosen by `Key` to the id and message of each commit.
+pub type Type = DashMap<u64, (String, String)>;
@@ -2,29 +2,586 @@
 pub struct FileInfo {
 	path:PathBuf,
 	last_modified:SystemTime,
+	/// BLAKE3 hash of the content last compiled, absent in older caches.
+	#[serde(default)]
+	hash:std::option::Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CompilerConfig {
 	Target:String,
 	Module:String,
+	/// Open CommonJS and AMD output with a `"use strict";` prologue. ES module
+	/// output is strict regardless and never gets one.
 	Strict:bool,
+	/// Emit `design:type` metadata for decorated members, which only legacy
+	/// decorators support, so it is ignored without `LegacyDecorators`.
 	EmitDecoratorsMetadata:bool,
+	/// Use the `experimentalDecorators` semantics of tsc instead of the
+	/// standard proposal, the only mode able to emit decorator metadata.
+	LegacyDecorators:bool,
+	/// Directory to emit into, `None` writes next to the source. Required for
+	/// `.js` and `.jsx` sources, whose output would otherwise replace them.
+	OutDir:std::option::Option<PathBuf>,
+	/// Prefix stripped from each source path before re-rooting it under `OutDir`.
+	Root:PathBuf,
+	/// Write a `.js.map` next to each emitted file.
+	SourceMaps:bool,
+	/// Embed the source map in each emitted file as a base64 data URI instead.
+	InlineSourceMap:bool,
+	/// JSX runtime for `.tsx` and `.jsx` inputs, `"classic"` or `"automatic"`.
+	JsxRuntime:String,
+	/// Factory used by the classic runtime, `React.createElement` when unset.
+	JsxPragma:std::option::Option<String>,
+	/// Compress the module and emit it without whitespace.
+	Minify:bool,
+	/// Carry leading and trailing comments through to the emitted JavaScript.
+	PreserveComments:bool,
+	/// Write a `.d.ts` next to each emitted file that has exports.
+	EmitDeclarations:bool,
+	/// TypeScript syntax accepted by the parser.
+	Ts:TsSyntaxConfig,
+	/// Escape every non-ASCII character in the emitted JavaScript.
+	AsciiOnly:bool,
+	/// Write nothing for sources holding only whitespace, instead of an empty
+	/// `.js`.
+	SkipEmpty:bool,
+	/// Point relative import and export specifiers at the emitted JavaScript,
+	/// so `./a.ts` becomes `./a.js`, as does `./a` when a source for it exists.
+	RewriteImports:bool,
+	/// Drop unreachable code and unused top-level declarations, keeping every
+	/// export and leaving the rest of the output as it is.
+	TreeShake:bool,
+}
+
+/// TypeScript syntax knobs handed to the parser, `tsx` aside, which follows
+/// the extension of each file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TsSyntaxConfig {
+	/// Accept `@decorator` syntax.
+	Decorators:bool,
+	/// Parse every file as a declaration file.
+	Dts:bool,
+	/// Skip the checks the parser would otherwise report as early errors.
+	NoEarlyErrors:bool,
+	/// Reject `<T> value` assertions and `<T>() => {}` type arguments, which
+	/// read as JSX.
+	DisallowAmbiguousJsxLike:bool,
+}
+
+impl Default for TsSyntaxConfig {
+	fn default() -> Self {
+		Self { Decorators:true, Dts:false, NoEarlyErrors:false, DisallowAmbiguousJsxLike:false }
+	}
+}
+
+impl TsSyntaxConfig {
+	/// The parser syntax for a file, `Tsx` saying whether it is a `.tsx`.
+	fn syntax(&self, Tsx:bool) -> TsSyntax {
+		TsSyntax {
+			tsx:Tsx,
+			decorators:self.Decorators,
+			dts:self.Dts,
+			no_early_errors:self.NoEarlyErrors,
+			disallow_ambiguous_jsx_like:self.DisallowAmbiguousJsxLike,
+		}
+	}
 }
 
 #[derive(Debug, Clone)]
 pub struct Option {
+	/// Paths to compile, each given as its components.
 	pub entry:Vec<Vec<String>>,
-	pub separator:char,
-	pub pattern:String,
+	/// Entry patterns, a file is compiled when it matches any of them. Those
+	/// matching `.js` or `.jsx` sources need `OutDir` set in `config`, or in
+	/// the `swc_config.json` nearest to them, as their output would otherwise
+	/// replace the source.
+	pub patterns:Vec<String>,
 	pub config:CompilerConfig,
+	/// File `config` was read from, watched for changes alongside the sources.
+	pub config_path:std::option::Option<PathBuf>,
+	/// Recompile every entry even when its mtime matches the cache.
+	pub force:bool,
+	/// Quiet period a watched path needs before it is recompiled.
+	pub debounce_ms:u64,
+	/// Glob patterns of files and directories never compiled or watched.
+	pub ignore:Vec<String>,
+	/// Upper bound on files read and compiled at the same time.
+	pub concurrency:usize,
+	/// Files compiled one after another by each spawned task, so projects of
+	/// many small files spend less time scheduling. `1` spawns one per file.
+	pub batch_size:usize,
+	/// Write a JSON `CompilerReport` here once a run completes.
+	pub report_path:std::option::Option<PathBuf>,
+	/// Compile every entry without writing anything to disk.
+	pub dry_run:bool,
+	/// Command run after each successful compile, with `{input}` and
+	/// `{output}` replaced by the source and emitted paths.
+	pub on_compiled:std::option::Option<String>,
+	/// Hide the progress bar and per-file lines, logging only failures and the
+	/// summary.
+	pub quiet:bool,
+	/// Retry reads failing with errors an editor mid-write can cause, as
+	/// watch mode does.
+	pub retry_reads:bool,
+	/// Skip test files, as matched by `test_patterns`, as if they were ignored.
+	pub exclude_tests:bool,
+	/// Glob patterns of test files and directories, matched like `ignore`.
+	pub test_patterns:Vec<String>,
+	/// Metrics every run accumulates into instead of its own, shared across
+	/// the runs of a watch session.
+	pub metrics:std::option::Option<Arc<Mutex<CompilerMetrics>>>,
+	/// How often a watch session rewrites `report_path` from `metrics`, `0`
+	/// never.
+	pub metrics_flush_secs:u64,
+	/// Abort the files still queued and end the run at the first error,
+	/// instead of compiling every file. The run still returns, with the error
+	/// in `CompilerRun::stopped`, so its report is written.
+	pub fail_fast:bool,
+	/// How long one file may take to compile before it is failed and the run
+	/// moves on, in seconds, `0` never.
+	pub per_file_timeout_secs:u64,
+	/// Concatenate the output of every entry compiled without errors into this
+	/// file once a run completes, sorted by source path and each preceded by a
+	/// `// --- <file> ---` line. Imports between the files are left as they
+	/// are, so this only suits sources that share globals rather than modules.
+	pub bundle:std::option::Option<PathBuf>,
+	/// Where the mtimes and hashes of compiled files are kept between runs,
+	/// `Cache::PATH` in the working directory by default.
+	pub cache_path:PathBuf,
+}
+
+impl Option {
+	/// Test files and directories skipped by default with `exclude_tests`.
+	pub const TEST_PATTERNS:[&'static str; 5] =
+		["*.test.ts", "*.spec.ts", "*.test.tsx", "*.spec.tsx", "__tests__"];
+
+	/// The patterns never compiled or watched, `ignore` followed by
+	/// `test_patterns` when `exclude_tests` is set.
+	pub fn ignored(&self) -> Vec<String> {
+		let mut Pattern = self.ignore.clone();
+
+		if self.exclude_tests {
+			Pattern.extend(self.test_patterns.iter().cloned());
+		}
+
+		Pattern
+	}
+
+	/// Directories whose `.restignore` applies, each entry or the directory
+	/// holding it when it names a file.
+	pub fn roots(&self) -> Vec<PathBuf> {
+		self.entry
+			.iter()
+			.map(|Entry| {
+				let Entry = Entry.iter().collect::<PathBuf>();
+
+				match Entry.is_file() {
+					true => Entry.parent().map(Path::to_path_buf).unwrap_or_default(),
+					false => Entry,
+				}
+			})
+			.collect()
+	}
+
+	/// Replaces `patterns` with the single `Pattern`.
+	pub fn with_pattern(self, Pattern:&str) -> Self {
+		Self { patterns:vec![Pattern.to_string()], ..self }
+	}
+}
+
+/// Entry pattern, either a glob such as `src/**/*.ts` or, when it holds no
+/// glob metacharacters, a plain suffix such as `.ts`.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+	Glob(GlobMatcher),
+	Suffix(String),
+}
+
+impl Pattern {
+	pub fn new(Pattern:&str) -> Result<Self> {
+		Ok(if Pattern.contains(['*', '?', '[', '{']) {
+			Self::Glob(Glob::new(Pattern)?.compile_matcher())
+		} else {
+			Self::Suffix(Pattern.to_string())
+		})
+	}
+
+	pub fn is_match(&self, File:&Path) -> bool {
+		match self {
+			Self::Glob(Glob) => Glob.is_match(File.strip_prefix(".").unwrap_or(File)),
+			Self::Suffix(Suffix) => File.to_string_lossy().ends_with(Suffix.as_str()),
+		}
+	}
+}
+
+/// Compiled entry `patterns`. Declaration files such as `index.d.ts` never
+/// match, whichever pattern would otherwise accept them.
+#[derive(Debug, Clone)]
+pub struct Patterns(Vec<Pattern>);
+
+impl Patterns {
+	pub fn new(Pattern:&[String]) -> Result<Self> {
+		Ok(Self(Pattern.iter().map(|Pattern| self::Pattern::new(Pattern)).collect::<Result<_>>()?))
+	}
+
+	pub fn is_match(&self, File:&Path) -> bool {
+		let Name = File.file_name().unwrap_or_default().to_string_lossy();
+
+		!(Name.ends_with(".d.ts") || Name.ends_with(".d.mts") || Name.ends_with(".d.cts"))
+			&& self.0.iter().any(|Pattern| Pattern.is_match(File))
+	}
+}
+
+/// Compiled `ignore` patterns, matched against the whole path and each of its
+/// components so `node_modules` excludes everything beneath it, together with
+/// the rules of the `.restignore` in the working directory and in each root,
+/// which only ever exclude more. Each file of rules applies beneath its own
+/// directory.
+#[derive(Debug, Clone)]
+pub struct Ignore {
+	Pattern:GlobSet,
+	Rule:Vec<Gitignore>,
+	/// The working directory relative paths are resolved against.
+	Current:PathBuf,
+}
+
+impl Ignore {
+	/// File holding `.gitignore`-style rules for the directory it is in.
+	pub const FILE:&'static str = ".restignore";
+
+	pub fn new(Pattern:&[String], Root:&[PathBuf]) -> Result<Self> {
+		let mut Builder = GlobSetBuilder::new();
+
+		for Pattern in Pattern {
+			Builder.add(Glob::new(Pattern)?);
+		}
+
+		let Current = std::env::current_dir()?;
+
+		let mut Directory = vec![Current.clone()];
+
+		for Root in Root {
+			let Root = Self::absolute(&Current, Root);
+
+			if !Directory.contains(&Root) {
+				Directory.push(Root);
+			}
+		}
+
+		let mut Rule = Vec::new();
+
+		for Directory in Directory {
+			let File = Directory.join(Self::FILE);
+
+			if !File.is_file() {
+				continue;
+			}
+
+			let mut Builder = GitignoreBuilder::new(&Directory);
+
+			if let Some(Error) = Builder.add(&File) {
+				return Err(anyhow!("Cannot read {}: {}", File.display(), Error));
+			}
+
+			Rule.push(Builder.build()?);
+		}
+
+		Ok(Self { Pattern:Builder.build()?, Rule, Current })
+	}
+
+	pub fn is_match(&self, File:&Path) -> bool {
+		if self.Pattern.is_match(File)
+			|| File.components().any(|Part| self.Pattern.is_match(Part.as_os_str()))
+		{
+			return true;
+		}
+
+		let Absolute = Self::absolute(&self.Current, File);
+
+		self.Rule.iter().filter(|Rule| Absolute.starts_with(Rule.path())).any(|Rule| {
+			Rule.matched_path_or_any_parents(&Absolute, File.is_dir()).is_ignore()
+		})
+	}
+
+	/// `Path` joined onto `Current` unless already absolute, without its `.`
+	/// components, so paths given either way compare equal.
+	fn absolute(Current:&Path, Path:&Path) -> PathBuf {
+		Current.join(Path).components().filter(|Part| *Part != Component::CurDir).collect()
+	}
 }
 
+/// Last seen modification time and content hash of every successfully
+/// compiled file, persisted as a list of `FileInfo` between runs.
 #[derive(Debug, Default)]
+pub struct Cache {
+	Entry:DashMap<PathBuf, (SystemTime, std::option::Option<String>)>,
+}
+
+impl Cache {
+	pub const PATH:&'static str = ".rest-cache.json";
+
+	pub async fn load(Path:&Path) -> Self {
+		let Entry = DashMap::new();
+
+		if let Ok(Content) = tokio::fs::read_to_string(Path).await {
+			match serde_json::from_str::<Vec<FileInfo>>(&Content) {
+				Ok(Info) => {
+					for FileInfo { path, last_modified, hash } in Info {
+						Entry.insert(path, (last_modified, hash));
+					}
+				},
+				Err(Error) => warn!("Cannot read cache {}: {}", Path.display(), Error),
+			}
+		}
+
+		Self { Entry }
+	}
+
+	pub fn is_fresh(&self, File:&Path, Modified:SystemTime) -> bool {
+		self.Entry.get(File).is_some_and(|Cached| Cached.0 == Modified)
+	}
+
+	/// Whether the content last compiled from `File` hashed to `Hash`.
+	pub fn is_same(&self, File:&Path, Hash:&str) -> bool {
+		self.Entry.get(File).is_some_and(|Cached| Cached.1.as_deref() == Some(Hash))
+	}
+
+	/// Whether `File` was compiled by an earlier run.
+	pub fn contains(&self, File:&Path) -> bool { self.Entry.contains_key(File) }
+
+	pub fn update(&self, File:PathBuf, Modified:SystemTime, Hash:String) {
+		self.Entry.insert(File, (Modified, Some(Hash)));
+	}
+
+	/// The BLAKE3 hash `is_same` and `update` expect for `Content`.
+	pub fn hash(Content:&str) -> String { blake3::hash(Content.as_bytes()).to_hex().to_string() }
+
+	/// Writes the entries to `Path`, merged with those already there, so runs
+	/// overlapping in a watch session keep each other's files. Where both hold
+	/// a file the later modification time wins. Saves within this process take
+	/// turns.
+	pub async fn save(&self, Path:&Path) -> Result<()> {
+		static TURN:Mutex<()> = Mutex::const_new(());
+
+		let _Turn = TURN.lock().await;
+
+		let Saved = Self::load(Path).await;
+
+		for Entry in self.Entry.iter() {
+			if Saved.Entry.get(Entry.key()).is_none_or(|Saved| Saved.0 <= Entry.value().0) {
+				Saved.Entry.insert(Entry.key().clone(), Entry.value().clone());
+			}
+		}
+
+		let mut Info = Saved
+			.Entry
+			.iter()
+			.map(|Entry| {
+				FileInfo {
+					path:Entry.key().clone(),
+					last_modified:Entry.value().0,
+					hash:Entry.value().1.clone(),
+				}
+			})
+			.collect::<Vec<_>>();
+
+		Info.sort_by(|A, B| A.path.cmp(&B.path));
+
+		tokio::fs::write(Path, serde_json::to_vec_pretty(&Info)?).await?;
+
+		Ok(())
+	}
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct CompilerMetrics {
-	Count:usize,
-	Elapsed:Duration,
-	Error:usize,
+	pub Count:usize,
+	pub Elapsed:Duration,
+	pub Error:usize,
+	/// Time spent compiling each file, keyed by its source path.
+	pub File:DashMap<PathBuf, Duration>,
+	/// Post-compile hooks that could not be run or exited non-zero.
+	pub Hook:usize,
+	/// Non-fatal diagnostics reported by the transforms.
+	pub Warning:usize,
+	/// Files left alone because the cache held them as unchanged.
+	pub Skipped:usize,
+}
+
+/// Machine-readable summary of a run, written to `report_path`.
+#[derive(Debug, Serialize)]
+pub struct CompilerReport {
+	pub files_processed:usize,
+	pub errors:usize,
+	pub hooks_failed:usize,
+	pub warnings:usize,
+	pub skipped:usize,
+	pub total_ms:u128,
+	pub slowest:Vec<CompilerTiming>,
+}
+
+/// What `compile_file` produced for one source.
+#[derive(Debug)]
+pub struct CompilerOutput {
+	/// The emitted JavaScript path, prefixed with `(dry-run)` when nothing was
+	/// written.
+	pub path:String,
+	/// Non-fatal diagnostics, formatted as `File:line:col: message`.
+	pub warnings:Vec<String>,
+}
+
+/// What `compile_str` emitted for one source, held in memory.
+#[derive(Debug)]
+pub struct CompileOutput {
+	/// The JavaScript, ending in an inline source map with `InlineSourceMap`.
+	pub code:String,
+	/// The source map with `SourceMaps`, without the comment linking to it.
+	pub map:std::option::Option<String>,
+	/// The declarations with `EmitDeclarations`, when there are exports.
+	pub declaration:std::option::Option<String>,
+	/// Non-fatal diagnostics, formatted as `File:line:col: message`.
+	pub warnings:Vec<String>,
+}
+
+/// Why `compile_file` or `compile_str` could not compile a source. Every
+/// message but that of `Io` names the source, as `File:line:col` where there
+/// is a position to point at.
+#[derive(Debug)]
+pub enum CompilerError {
+	/// The source is not valid TypeScript.
+	Parse(String),
+	/// The configuration or a transform rejected the parsed module.
+	Transform(String),
+	/// The JavaScript, its source map or its declarations could not be
+	/// produced.
+	Emit(String),
+	/// The source could not be read.
+	Read(String, std::io::Error),
+	/// The output could not be written.
+	Io(std::io::Error),
+	/// The file took longer than the given number of seconds to compile.
+	Timeout(String, u64),
+}
+
+impl std::fmt::Display for CompilerError {
+	fn fmt(&self, Formatter:&mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Parse(Message) => write!(Formatter, "Cannot parse {}", Message),
+			Self::Transform(Message) => write!(Formatter, "Cannot transform {}", Message),
+			Self::Emit(Message) => write!(Formatter, "Cannot emit {}", Message),
+			Self::Read(File, Error) => write!(Formatter, "Cannot read {}: {}", File, Error),
+			Self::Io(Error) => write!(Formatter, "Cannot write output: {}", Error),
+			Self::Timeout(File, Limit) => {
+				write!(Formatter, "Cannot compile {} within {}s", File, Limit)
+			},
+		}
+	}
+}
+
+impl std::error::Error for CompilerError {
+	fn source(&self) -> std::option::Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Read(_, Error) | Self::Io(Error) => Some(Error),
+			_ => None,
+		}
+	}
+}
+
+impl From<std::io::Error> for CompilerError {
+	fn from(Error:std::io::Error) -> Self { Self::Io(Error) }
+}
+
+/// Everything one `Compile::Fn` run produced.
+#[derive(Debug)]
+pub struct CompilerRun {
+	/// Each source path with the emitted path, or the error it failed with.
+	pub results:Vec<(String, std::result::Result<String, String>)>,
+	pub report:CompilerReport,
+	/// The failure `fail_fast` stopped the run at, leaving the files after it
+	/// out of `results`.
+	pub stopped:std::option::Option<String>,
+}
+
+/// Machine-readable outcome of a run for CI, `ok` only when nothing failed.
+#[derive(Debug, Serialize)]
+pub struct CompilerSummary {
+	pub ok:bool,
+	pub compiled:usize,
+	pub failed:usize,
+	pub files:Vec<CompilerFile>,
+}
+
+/// One source of a `CompilerSummary`, with either its output or its error.
+#[derive(Debug, Serialize)]
+pub struct CompilerFile {
+	pub path:String,
+	#[serde(skip_serializing_if = "std::option::Option::is_none")]
+	pub output:std::option::Option<String>,
+	#[serde(skip_serializing_if = "std::option::Option::is_none")]
+	pub error:std::option::Option<String>,
+}
+
+impl CompilerRun {
+	pub fn summary(&self) -> CompilerSummary {
+		CompilerSummary {
+			ok:self.report.errors == 0,
+			compiled:self.results.iter().filter(|(_, Result)| Result.is_ok()).count(),
+			failed:self.report.errors,
+			files:self
+				.results
+				.iter()
+				.map(|(path, Result)| {
+					CompilerFile {
+						path:path.clone(),
+						output:Result.as_ref().ok().cloned(),
+						error:Result.as_ref().err().cloned(),
+					}
+				})
+				.collect(),
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompilerTiming {
+	pub path:PathBuf,
+	pub ms:u128,
+}
+
+impl CompilerMetrics {
+	/// The `n` files that took longest to compile, slowest first.
+	pub fn slowest(&self, n:usize) -> Vec<(PathBuf, Duration)> {
+		let mut File = self
+			.File
+			.iter()
+			.map(|Entry| (Entry.key().clone(), *Entry.value()))
+			.collect::<Vec<_>>();
+
+		File.sort_by_key(|(_, Elapsed)| std::cmp::Reverse(*Elapsed));
+
+		File.truncate(n);
+
+		File
+	}
+
+	pub fn report(&self) -> CompilerReport {
+		CompilerReport {
+			files_processed:self.Count,
+			errors:self.Error,
+			hooks_failed:self.Hook,
+			warnings:self.Warning,
+			skipped:self.Skipped,
+			total_ms:self.Elapsed.as_millis(),
+			slowest:self
+				.slowest(10)
+				.into_iter()
+				.map(|(path, Elapsed)| CompilerTiming { path, ms:Elapsed.as_millis() })
+				.collect(),
+		}
+	}
 }
 
 impl Default for CompilerConfig {
@@ -34,81 +591,1948 @@ impl Default for CompilerConfig {
 			Module:"commonjs".to_string(),
 			Strict:true,
 			EmitDecoratorsMetadata:true,
+			LegacyDecorators:false,
+			OutDir:None,
+			Root:PathBuf::from("."),
+			SourceMaps:false,
+			InlineSourceMap:false,
+			JsxRuntime:"classic".to_string(),
+			JsxPragma:None,
+			Minify:false,
+			PreserveComments:false,
+			EmitDeclarations:false,
+			Ts:TsSyntaxConfig::default(),
+			AsciiOnly:false,
+			SkipEmpty:false,
+			RewriteImports:false,
+			TreeShake:false,
+		}
+	}
+}
+
+impl CompilerConfig {
+	pub const PATH:&'static str = "swc_config.json";
+
+	pub const TSCONFIG:&'static str = "tsconfig.json";
+
+	/// Prefix of the environment variables `env` overlays.
+	pub const ENV:&'static str = "REST_";
+
+	/// Reads and validates the JSON configuration at `Path`.
+	pub async fn load(Path:&Path) -> Result<Self> {
+		let Content = tokio::fs::read_to_string(Path)
+			.await
+			.with_context(|| format!("Cannot read {}", Path.display()))?;
+
+		let Config = serde_json::from_str::<Self>(&Content)
+			.with_context(|| format!("Cannot parse {}", Path.display()))?;
+
+		Config.validate().with_context(|| format!("Invalid config {}", Path.display()))?;
+
+		Ok(Config)
+	}
+
+	/// Reads `Path` with `from_tsconfig` when it is named `tsconfig.json` and
+	/// with `load` otherwise.
+	pub async fn from_path(Path:&Path) -> Result<Self> {
+		match Path.file_name() {
+			Some(Name) if Name == Self::TSCONFIG => Self::from_tsconfig(Path).await,
+			_ => Self::load(Path).await,
+		}
+	}
+
+	/// Derives a configuration from the `compilerOptions` of a `tsconfig.json`,
+	/// keeping the default for every option that is absent or unsupported.
+	pub async fn from_tsconfig(Path:&Path) -> Result<Self> {
+		let Content = tokio::fs::read_to_string(Path)
+			.await
+			.with_context(|| format!("Cannot read {}", Path.display()))?;
+
+		let Content = serde_json::from_str::<serde_json::Value>(&Content)
+			.with_context(|| format!("Cannot parse {}", Path.display()))?;
+
+		let Option = &Content["compilerOptions"];
+
+		let mut Config = Self::default();
+
+		if let Some(Target) = Option["target"].as_str() {
+			if parse_target(Target).is_ok() {
+				Config.Target = Target.to_lowercase();
+			}
+		}
+
+		if let Some(Module) = Option["module"].as_str() {
+			match Module.to_lowercase().as_str() {
+				"commonjs" => Config.Module = "commonjs".to_string(),
+				"amd" => Config.Module = "amd".to_string(),
+				"es6" | "es2015" | "es2020" | "es2022" | "esnext" => Config.Module = "es6".to_string(),
+				_ => {},
+			}
+		}
+
+		if let Some(Strict) = Option["strict"].as_bool() {
+			Config.Strict = Strict;
+		}
+
+		if let Some(LegacyDecorators) = Option["experimentalDecorators"].as_bool() {
+			Config.LegacyDecorators = LegacyDecorators;
+		}
+
+		if let Some(EmitDecoratorsMetadata) = Option["emitDecoratorMetadata"].as_bool() {
+			Config.EmitDecoratorsMetadata = EmitDecoratorsMetadata;
+		}
+
+		Config.validate().with_context(|| format!("Invalid config {}", Path.display()))?;
+
+		Ok(Config)
+	}
+
+	/// Overlays the fields named by `REST_` environment variables, such as
+	/// `REST_TARGET=es2018`, and validates the result. Names match ignoring
+	/// case and underscores, so `REST_OUT_DIR` sets `OutDir` and `REST_TS_DTS`
+	/// sets `Ts.Dts`. Values of string fields are taken as they are, others
+	/// parse as JSON. Settings resolve as defaults, then the file, then the
+	/// environment, then the command line.
+	pub fn env(self) -> Result<Self> { self.overlay(std::env::vars_os()) }
+
+	/// `env` over the variables of `Variable`, skipping those whose name is
+	/// not valid UTF-8 and warning about `REST_` ones whose value is not.
+	fn overlay(self, Variable:impl IntoIterator<Item = (OsString, OsString)>) -> Result<Self> {
+		let mut Value = serde_json::to_value(&self)?;
+
+		let mut Overlaid = false;
+
+		for (Name, Raw) in Variable {
+			let Some((Name, Field)) =
+				Name.to_str().and_then(|Name| Some((Name, Name.strip_prefix(Self::ENV)?)))
+			else {
+				continue;
+			};
+
+			let Some(Raw) = Raw.to_str() else {
+				warn!("{}: Is not valid UTF-8", Name);
+				continue;
+			};
+
+			match Overlay(&mut Value, &Field.replace('_', "").to_lowercase(), Raw) {
+				true => Overlaid = true,
+				false => warn!("{}: Names no config field", Name),
+			}
+		}
+
+		if !Overlaid {
+			return Ok(self);
+		}
+
+		let Config = serde_json::from_value::<Self>(Value)
+			.with_context(|| format!("Cannot parse {} variables", Self::ENV))?;
+
+		Config.validate().with_context(|| format!("Invalid {} variables", Self::ENV))?;
+
+		Ok(Config)
+	}
+
+	/// Rejects values the compiler does not understand and combinations that
+	/// cannot produce working output, naming the field, and warns about those
+	/// that are allowed but questionable.
+	pub fn validate(&self) -> Result<()> {
+		let Target = parse_target(&self.Target).context("Target")?;
+
+		let Module = self.Module.to_lowercase();
+
+		if !matches!(Module.as_str(), "commonjs" | "amd" | "es6" | "esm") {
+			return Err(anyhow!(
+				"Module: Unknown Module: {}, expected commonjs, amd, es6 or esm",
+				self.Module
+			));
+		}
+
+		if matches!(Module.as_str(), "es6" | "esm") {
+			match Target {
+				EsVersion::Es3 => {
+					return Err(anyhow!(
+						"Module: {} emits import and export, which Target {} cannot run",
+						self.Module,
+						self.Target
+					));
+				},
+				EsVersion::Es5 => {
+					warn!(
+						"Module {} with Target {} only runs once bundled",
+						self.Module, self.Target
+					)
+				},
+				_ => {},
+			}
+		}
+
+		if self.Minify && self.PreserveComments {
+			warn!("PreserveComments: Most comments are still dropped by Minify");
+		}
+
+		if self.SourceMaps && self.InlineSourceMap {
+			return Err(anyhow!("InlineSourceMap: Cannot be combined with SourceMaps"));
 		}
+
+		if !matches!(self.JsxRuntime.to_lowercase().as_str(), "classic" | "automatic") {
+			return Err(anyhow!(
+				"JsxRuntime: Unknown JsxRuntime: {}, expected classic or automatic",
+				self.JsxRuntime
+			));
+		}
+
+		Ok(())
 	}
 }
 
+/// The JavaScript, source map and declarations emitted for one file.
+type Emit = (Vec<u8>, std::option::Option<Vec<u8>>, std::option::Option<Vec<u8>>);
+
 #[derive(Debug)]
 pub struct Compiler {
 	config:CompilerConfig,
-	Outlook:Arc<Mutex<CompilerMetrics>>,
+	pub Outlook:Arc<Mutex<CompilerMetrics>>,
+	/// Emit into memory only, leaving the filesystem untouched.
+	pub DryRun:bool,
+	/// The nearest `swc_config.json` above each directory seen and its config,
+	/// `None` where `config` applies.
+	Local:DashMap<PathBuf, std::option::Option<(PathBuf, CompilerConfig)>>,
 }
 
 impl Compiler {
 	pub fn new(config:CompilerConfig) -> Self {
-		Self { config, Outlook:Arc::new(Mutex::new(CompilerMetrics::default())) }
+		Self {
+			config,
+			Outlook:Arc::new(Mutex::new(CompilerMetrics::default())),
+			DryRun:false,
+			Local:DashMap::new(),
+		}
 	}
 
-	#[tracing::instrument(skip(self, input))]
-	async fn compile_file(&self, File:&str, input:String) -> Result<String> {
-		let Begin = Instant::now();
+	/// Compiles `File` with the config of the nearest `swc_config.json` in a
+	/// directory above it, below the working directory, so packages of a
+	/// monorepo can differ. Without one the config of the compiler applies.
+	pub async fn compile_file(
+		&self,
+		File:&str,
+		input:String,
+	) -> std::result::Result<CompilerOutput, CompilerError> {
+		self.compile_file_unless(File, input, &AtomicBool::new(false)).await
+	}
 
-		let cm = SourceMap::new(FilePathMapping::empty());
+	/// `compile_file`, setting `Claim` once the output is about to be written
+	/// and giving up without writing or counting anything when it is already
+	/// set, so a caller that stopped waiting can tell whether it will appear.
+	#[tracing::instrument(
+		skip(self, File, input, Claim),
+		fields(file = %File, bytes = input.len())
+	)]
+	pub async fn compile_file_unless(
+		&self,
+		File:&str,
+		input:String,
+		Claim:&AtomicBool,
+	) -> std::result::Result<CompilerOutput, CompilerError> {
+		match self.resolve(File).await? {
+			Some((_, config)) => {
+				Self {
+					config,
+					Outlook:Arc::clone(&self.Outlook),
+					DryRun:self.DryRun,
+					Local:DashMap::new(),
+				}
+				.write(File, input, Claim)
+				.await
+			},
+			None => self.write(File, input, Claim).await,
+		}
+	}
 
-		let source_file = cm.new_source_file(FileName::Real(File.into()), input);
+	/// Where `compile_file` writes `File`, with the `swc_config.json` deciding
+	/// it when that is not the config of the compiler.
+	pub async fn Emitted(
+		&self,
+		File:&str,
+	) -> std::result::Result<(PathBuf, std::option::Option<PathBuf>), CompilerError> {
+		Ok(match self.resolve(File).await? {
+			Some((Source, config)) => (Self::new(config).Output(File), Some(Source)),
+			None => (self.Output(File), None),
+		})
+	}
 
-		let mut parser = Parser::new_from(Lexer::new(
-			Syntax::Typescript(TsConfig { decorators:true, ..Default::default() }),
-			EsVersion::Es2022,
-			StringInput::from(&*source_file),
-			None,
-		));
+	/// Finds the nearest `swc_config.json` above `File` and its config,
+	/// stopping at the working directory, whose config the compiler already
+	/// holds. Each directory is resolved once.
+	async fn resolve(
+		&self,
+		File:&str,
+	) -> std::result::Result<std::option::Option<(PathBuf, CompilerConfig)>, CompilerError> {
+		let Some(Directory) = Path::new(File).parent() else {
+			return Ok(None);
+		};
+
+		if let Some(Config) = self.Local.get(Directory) {
+			return Ok(Config.clone());
+		}
 
-		let mut Parsed = parser.parse_module().expect("Failed to parse TypeScript module")?;
+		let Current = std::env::current_dir().ok();
 
-		let Unresolved = Mark::new();
+		let mut Config = None;
 
-		let Top = Mark::new();
+		for Ancestor in Directory.ancestors() {
+			if matches!(Ancestor.to_str(), Some("" | "."))
+				|| Current.as_deref().is_some_and(|Current| Ancestor == Current)
+			{
+				break;
+			}
 
-		Parsed = Parsed.fold_with(&mut swc_ecma_transforms_base::resolver(Unresolved, Top, true));
+			if let Some(Cached) = self.Local.get(Ancestor) {
+				Config = Cached.clone();
+				break;
+			}
 
-		Parsed = Parsed.fold_with(&mut swc_ecma_transforms_typescript::strip(Unresolved, Top));
+			let Candidate = Ancestor.join(CompilerConfig::PATH);
 
-		Parsed = Parsed.fold_with(&mut decorators::decorators(decorators::Config {
-			legacy:false,
-			emit_metadata:self.config.EmitDecoratorsMetadata,
-			use_define_for_class_fields:true,
-			..Default::default()
-		}));
+			if tokio::fs::try_exists(&Candidate).await.unwrap_or(false) {
+				let Loaded = CompilerConfig::load(&Candidate)
+					.await
+					.and_then(CompilerConfig::env)
+					.map_err(|Error| CompilerError::Transform(format!("{}: {:#}", File, Error)))?;
 
-		// Parsed = Parsed.fold_with(&mut InjectHelpers::default());
+				Config = Some((Candidate, Loaded));
 
-		let mut Output = vec![];
+				break;
+			}
+		}
 
-		let mut Emitter = Emitter {
-			cfg:swc_ecma_codegen::Config::default(),
-			cm:cm.into().clone(),
-			comments:None,
-			wr:JsWriter::new(cm.into(), "\n", &mut Output, None),
-		};
+		self.Local.insert(Directory.to_path_buf(), Config.clone());
+
+		Ok(Config)
+	}
+
+	/// Compiles `File` with the config of the compiler and writes the output,
+	/// unless `Claim` is set by then.
+	async fn write(
+		&self,
+		File:&str,
+		input:String,
+		Claim:&AtomicBool,
+	) -> std::result::Result<CompilerOutput, CompilerError> {
+		let Begin = Instant::now();
+
+		let Path = self.Output(File);
+
+		if Path == std::path::Path::new(File) {
+			return Err(CompilerError::Emit(format!(
+				"{}: Output would replace the source, set OutDir",
+				File
+			)));
+		}
+
+		let Empty = input.trim().is_empty();
+
+		// Whitespace compiles to nothing, so the parser is not bothered with it.
+		let CompileOutput { code:mut Output, map:Map, declaration:Declaration, warnings } =
+			match Empty {
+				true => {
+					CompileOutput { code:String::new(), map:None, declaration:None, warnings:Vec::new() }
+				},
+				false => self.compile_str(File, input)?,
+			};
+
+		if Claim.swap(true, Ordering::SeqCst) {
+			return Err(CompilerError::Transform(format!("{}: Abandoned", File)));
+		}
+
+		let Skip = self.DryRun || (Empty && self.config.SkipEmpty);
+
+		if !Skip {
+			if let Some(Parent) = Path.parent() {
+				tokio::fs::create_dir_all(Parent).await?;
+			}
+
+			if let Some(Declaration) = Declaration {
+				Write(&Path.with_extension("d.ts"), Declaration.as_bytes()).await?;
+			}
 
-		Emitter.emit_module(&Parsed).expect("Failed to emit JavaScript")?;
+			if let Some(Source) = Map {
+				let Map = Path.with_extension("js.map");
 
-		let Path = Path::new(File).with_extension("js");
+				Write(&Map, Source.as_bytes()).await?;
 
-		tokio::fs::write(&Path, &Output).await.expect("Failed to write output file")?;
+				Output.push_str(&format!(
+					"\n//# sourceMappingURL={}\n",
+					Map.file_name().unwrap_or_default().to_string_lossy()
+				));
+			}
+
+			Write(&Path, Output.as_bytes()).await?;
+
+			// Keeps scripts with a shebang executable once compiled.
+			#[cfg(unix)]
+			if let Ok(Metadata) = tokio::fs::metadata(File).await {
+				tokio::fs::set_permissions(&Path, Metadata.permissions()).await?;
+			}
+		}
 
 		let Elapsed = Begin.elapsed();
 
 		let mut Outlook = self.Outlook.lock().await;
 		Outlook.Count += 1;
 		Outlook.Elapsed += Elapsed;
+		Outlook.File.insert(PathBuf::from(File), Elapsed);
+
+		trace!(elapsed = ?Elapsed, output_bytes = Output.len(), "Compiled {}", File);
 
-		debug!("Compiled {} in {:?}", File, Elapsed);
+		let path = match self.DryRun {
+			true => format!("(dry-run) {}", Path.display()),
+			false => Path.to_string_lossy().to_string(),
+		};
 
-		Ok(Path.to_string_lossy().to_string())
+		Ok(CompilerOutput { path, warnings })
 	}
-}
 
-use serde::{Deserialize, Serialize};
-use tracing::debug;
+	/// Compiles `input` as if read from `File` and returns what would be
+	/// emitted for it, without touching the filesystem or the metrics. The
+	/// source map is relative to where `compile_file` would write it.
+	pub fn compile_str(
+		&self,
+		File:&str,
+		input:String,
+	) -> std::result::Result<CompileOutput, CompilerError> {
+		let Path = self.Output(File);
+
+		let ((Output, Map, Declaration), warnings) = self.scoped(File, &Path, input)?;
+
+		let Text = |Content:Vec<u8>| {
+			String::from_utf8(Content)
+				.map_err(|Error| CompilerError::Emit(format!("{}: {}", File, Error)))
+		};
+
+		Ok(CompileOutput {
+			code:Text(Output)?,
+			map:Map.map(Text).transpose()?,
+			declaration:Declaration.map(Text).transpose()?,
+			warnings,
+		})
+	}
+
+	/// Compiles every source of `Files`, keyed by the path it would be read from,
+	/// in parallel with `compile_str`, returning each result under the same
+	/// path. Nothing is read from or written to disk, so `swc_config.json`
+	/// files above the paths are not consulted either.
+	pub fn compile_vfs(
+		&self,
+		Files:HashMap<String, String>,
+	) -> HashMap<String, std::result::Result<CompileOutput, CompilerError>> {
+		Files
+			.into_par_iter()
+			.map(|(File, input)| {
+				let Output = self.compile_str(&File, input);
+
+				(File, Output)
+			})
+			.collect()
+	}
+
+	/// Runs `transform` inside fresh `GLOBALS`, `HELPERS` and a `HANDLER`
+	/// buffering what the transforms report, failing on the first error and
+	/// returning the warnings alongside the emit.
+	fn scoped(
+		&self,
+		File:&str,
+		Path:&Path,
+		input:String,
+	) -> std::result::Result<(Emit, Vec<String>), CompilerError> {
+		let cm:Lrc<SourceMap> = Default::default();
+
+		let Buffer = Diagnostics::default();
+
+		let Handler = Handler::with_emitter(true, false, Box::new(Buffer.clone()));
+
+		let Emit = GLOBALS.set(&Globals::new(), || {
+			HELPERS.set(&Helpers::new(false), || {
+				HANDLER.set(&Handler, || self.transform(File, Path, &cm, input))
+			})
+		});
+
+		let (Error, Warning) = Buffer.take(&cm, File);
+
+		if let Some(Error) = Error.into_iter().next() {
+			return Err(CompilerError::Transform(Error));
+		}
+
+		Ok((Emit?, Warning))
+	}
+
+	/// Parses, transforms and emits `File`, returning the JavaScript, its source
+	/// map pointing back at `File` from the directory of `Path` when
+	/// `SourceMaps` is set, and its declarations when `EmitDeclarations` is set.
+	/// With `InlineSourceMap` the map is appended to the JavaScript instead.
+	/// A leading byte order mark is dropped before parsing.
+	/// Must run inside `GLOBALS`, `HELPERS` and `HANDLER`, with `cm` the map
+	/// the handler locates diagnostics in.
+	fn transform(
+		&self,
+		File:&str,
+		Path:&Path,
+		cm:&Lrc<SourceMap>,
+		mut input:String,
+	) -> std::result::Result<Emit, CompilerError> {
+		let Reject =
+			|Error:&dyn std::fmt::Display| CompilerError::Transform(format!("{}: {}", File, Error));
+
+		let Fail =
+			|Error:&dyn std::fmt::Display| CompilerError::Emit(format!("{}: {}", File, Error));
+
+		if input.starts_with('\u{feff}') {
+			input.drain(..'\u{feff}'.len_utf8());
+		}
+
+		let Target = parse_target(&self.config.Target).map_err(|Error| Reject(&Error))?;
+
+		let Extension = Path::new(File).extension().and_then(|Extension| Extension.to_str());
+
+		// Plain JavaScript is parsed without types and never stripped.
+		let Script = matches!(Extension, Some("js" | "jsx"));
+
+		let Jsx = matches!(Extension, Some("tsx" | "jsx"));
+
+		let source_file = cm.new_source_file(Lrc::new(FileName::Real(File.into())), input);
+
+		let Comment = SingleThreadedComments::default();
+
+		let Comments = self.config.PreserveComments.then_some(&Comment as &dyn Comments);
+
+		let Syntax = match Script {
+			true => {
+				Syntax::Es(EsSyntax {
+					jsx:Jsx,
+					decorators:self.config.Ts.Decorators,
+					..Default::default()
+				})
+			},
+			false => Syntax::Typescript(self.config.Ts.syntax(Jsx)),
+		};
+
+		let mut parser = Parser::new_from(Lexer::new(
+			Syntax,
+			Target,
+			StringInput::from(&*source_file),
+			Comments,
+		));
+
+		let Module = parser.parse_module();
+
+		let mut Diagnostic = parser.take_errors();
+
+		let mut Parsed = match Module {
+			Ok(Parsed) if Diagnostic.is_empty() => Program::Module(Parsed),
+			Ok(_) => {
+				let Error = Diagnostic.remove(0);
+
+				return Err(CompilerError::Parse(Locate(
+					cm,
+					File,
+					Error.span(),
+					Error.kind().msg(),
+				)));
+			},
+			Err(Error) => {
+				return Err(CompilerError::Parse(Locate(
+					cm,
+					File,
+					Error.span(),
+					Error.kind().msg(),
+				)));
+			},
+		};
+
+		let Declaration = match &Parsed {
+			Program::Module(Module) if self.config.EmitDeclarations && !Script => {
+				Self::declare(File, cm, Module)?
+			},
+			_ => None,
+		};
+
+		let Unresolved = Mark::new();
+
+		let Top = Mark::new();
+
+		Parsed = Parsed.fold_with(&mut swc_ecma_transforms_base::resolver(Unresolved, Top, !Script));
+
+		// Decorators run before types are stripped so metadata can see them.
+		Parsed = Parsed.fold_with(&mut decorators::decorators(decorators::Config {
+			legacy:self.config.LegacyDecorators,
+			emit_metadata:self.config.EmitDecoratorsMetadata && self.config.LegacyDecorators,
+			use_define_for_class_fields:!self.config.LegacyDecorators,
+		}));
+
+		if !Script {
+			Parsed = Parsed.fold_with(&mut swc_ecma_transforms_typescript::strip(Unresolved, Top));
+		}
+
+		if Jsx {
+			Parsed = Parsed.fold_with(&mut swc_ecma_transforms_react::jsx(
+				cm.clone(),
+				self.config.PreserveComments.then(|| Comment.clone()),
+				swc_ecma_transforms_react::Options {
+					runtime:Some(match self.config.JsxRuntime.to_lowercase().as_str() {
+						"classic" => Runtime::Classic,
+						"automatic" => Runtime::Automatic,
+						_ => {
+							return Err(Reject(&format!(
+								"Unknown JsxRuntime: {}",
+								self.config.JsxRuntime
+							)))
+						},
+					}),
+					pragma:self.config.JsxPragma.clone(),
+					..Default::default()
+				},
+				Top,
+				Unresolved,
+			));
+		}
+
+		if self.config.RewriteImports {
+			Parsed.visit_mut_with(&mut Rewrite {
+				Directory:Path::new(File).parent().map(Path::to_path_buf).unwrap_or_default(),
+			});
+		}
+
+		// Exports are still declarations of the module here, so none of
+		// them is mistaken for unused.
+		if self.config.TreeShake {
+			Parsed = swc_ecma_minifier::optimize(
+				Parsed,
+				cm.clone(),
+				Comments,
+				None,
+				&MinifyOptions { compress:Some(Self::shake(Target)), ..Default::default() },
+				&ExtraOptions {
+					unresolved_mark:Unresolved,
+					top_level_mark:Top,
+					mangle_name_cache:None,
+				},
+			);
+		}
+
+		let Convert = swc_ecma_transforms_module::util::Config {
+			strict_mode:self.config.Strict,
+			..Default::default()
+		};
+
+		Parsed = match self.config.Module.to_lowercase().as_str() {
+			"commonjs" => {
+				Parsed.fold_with(&mut swc_ecma_transforms_module::common_js(
+					Resolver::Default,
+					Unresolved,
+					Convert,
+					enable_available_feature_from_es_version(Target),
+				))
+			},
+			"amd" => {
+				Parsed.fold_with(&mut swc_ecma_transforms_module::amd(
+					Resolver::Default,
+					Unresolved,
+					swc_ecma_transforms_module::amd::Config { module_id:None, config:Convert },
+					enable_available_feature_from_es_version(Target),
+					self.config.PreserveComments.then(|| Comment.clone()),
+				))
+			},
+			"es6" | "esm" => Parsed,
+			_ => return Err(Reject(&format!("Unknown Module: {}", self.config.Module))),
+		};
+
+		Parsed = Parsed.fold_with(&mut inject_helpers(Unresolved));
+
+		if self.config.Minify {
+			Parsed = swc_ecma_minifier::optimize(
+				Parsed,
+				cm.clone(),
+				Comments,
+				None,
+				&MinifyOptions { compress:Some(Default::default()), ..Default::default() },
+				&ExtraOptions {
+					unresolved_mark:Unresolved,
+					top_level_mark:Top,
+					mangle_name_cache:None,
+				},
+			);
+		}
+
+		let mut Output = vec![];
+
+		let mut Mapping = vec![];
+
+		Emitter {
+			cfg:swc_ecma_codegen::Config::default()
+				.with_target(Target)
+				.with_minify(self.config.Minify)
+				.with_ascii_only(self.config.AsciiOnly),
+			cm:cm.clone(),
+			comments:Comments,
+			wr:JsWriter::new(
+				cm.clone(),
+				"\n",
+				&mut Output,
+				(self.config.SourceMaps || self.config.InlineSourceMap).then_some(&mut Mapping),
+			),
+		}
+		.emit_program(&Parsed)
+		.map_err(|Error| Fail(&Error))?;
+
+		if !self.config.SourceMaps && !self.config.InlineSourceMap {
+			return Ok((Output, None, Declaration));
+		}
+
+		let mut Source = cm.build_source_map(&Mapping);
+
+		if Source.get_source_count() > 0 {
+			Source.set_source(
+				0,
+				&Relative(Path.parent().unwrap_or(Path::new("")), Path::new(File)).to_string_lossy(),
+			);
+		}
+
+		if self.config.InlineSourceMap {
+			Output.extend_from_slice(
+				format!(
+					"\n//# sourceMappingURL={}\n",
+					Source.to_data_url().map_err(|Error| Fail(&Error))?
+				)
+				.as_bytes(),
+			);
+
+			return Ok((Output, None, Declaration));
+		}
+
+		let mut Map = vec![];
+
+		Source.to_writer(&mut Map).map_err(|Error| Fail(&Error))?;
+
+		Ok((Output, Some(Map), Declaration))
+	}
+
+	/// Compression limited to removing dead code and unused declarations, with
+	/// every pass that would rewrite what is kept turned off.
+	fn shake(Target:EsVersion) -> CompressOptions {
+		CompressOptions {
+			dead_code:true,
+			unused:true,
+			side_effects:true,
+			top_level:Some(TopLevelOptions { functions:true }),
+			ecma:Target,
+			arrows:false,
+			bools:false,
+			collapse_vars:false,
+			comparisons:false,
+			computed_props:false,
+			conditionals:false,
+			directives:false,
+			drop_debugger:false,
+			evaluate:false,
+			hoist_props:false,
+			if_return:false,
+			inline:0,
+			join_vars:false,
+			loops:false,
+			negate_iife:false,
+			props:false,
+			reduce_fns:false,
+			sequences:0,
+			switches:false,
+			typeofs:false,
+			const_to_let:false,
+			..Default::default()
+		}
+	}
+
+	/// Emits the isolated declarations of `Module`, or `None` when it exports
+	/// nothing. Any construct whose type would need inferring is an error.
+	fn declare(
+		File:&str,
+		cm:&Lrc<SourceMap>,
+		Module:&Module,
+	) -> std::result::Result<std::option::Option<Vec<u8>>, CompilerError> {
+		if !Module.body.iter().any(|Item| {
+			matches!(Item, ModuleItem::ModuleDecl(Declaration) if !Declaration.is_import())
+		}) {
+			return Ok(None);
+		}
+
+		let mut Module = Module.clone();
+
+		let Issue = FastDts::new(std::sync::Arc::new(FileName::Real(File.into())))
+			.transform(&mut Module);
+
+		if let Some(Issue) = Issue.first() {
+			return Err(CompilerError::Emit(format!(
+				"declarations for {}",
+				Locate(cm, File, Issue.range().span, Issue)
+			)));
+		}
+
+		let mut Output = vec![];
+
+		Emitter {
+			cfg:Default::default(),
+			cm:cm.clone(),
+			comments:None,
+			wr:JsWriter::new(cm.clone(), "\n", &mut Output, None),
+		}
+		.emit_module(&Module)
+		.map_err(|Error| CompilerError::Emit(format!("declarations for {}: {}", File, Error)))?;
+
+		Ok(Some(Output))
+	}
+
+	/// Where the JavaScript compiled from `File` is written.
+	pub fn Output(&self, File:&str) -> PathBuf {
+		let Source = Path::new(File);
+
+		match &self.config.OutDir {
+			Some(OutDir) => {
+				match Source.strip_prefix(&self.config.Root) {
+					Ok(Relative) => OutDir.join(Relative).with_extension("js"),
+					Err(_) => {
+						warn!(
+							"{} lies outside {}, emitting in place.",
+							File,
+							self.config.Root.display()
+						);
+
+						Source.with_extension("js")
+					},
+				}
+			},
+			None => Source.with_extension("js"),
+		}
+	}
+}
+
+/// Maps a `Target` string such as `"es2022"` onto its `EsVersion`.
+pub fn parse_target(Target:&str) -> Result<EsVersion> {
+	Ok(match Target.to_lowercase().as_str() {
+		"es3" => EsVersion::Es3,
+		"es5" => EsVersion::Es5,
+		"es2015" | "es6" => EsVersion::Es2015,
+		"es2016" => EsVersion::Es2016,
+		"es2017" => EsVersion::Es2017,
+		"es2018" => EsVersion::Es2018,
+		"es2019" => EsVersion::Es2019,
+		"es2020" => EsVersion::Es2020,
+		"es2021" => EsVersion::Es2021,
+		"es2022" => EsVersion::Es2022,
+		"esnext" => EsVersion::EsNext,
+		_ => return Err(anyhow!("Unknown Target: {}", Target)),
+	})
+}
+
+/// Writes `Content` to a temporary sibling of `Path` and renames it into place,
+/// so readers never observe a partially written file. Falls back to writing
+/// `Path` directly when the rename fails.
+async fn Write(Path:&Path, Content:&[u8]) -> std::io::Result<()> {
+	Replace(Path, Content, |_| Ok(())).await
+}
+
+/// `Write`, calling `Staged` with the temporary file once it holds `Content`
+/// and before it is renamed. When `Staged` fails the temporary file is
+/// removed and `Path` is left as it was.
+async fn Replace(
+	Path:&Path,
+	Content:&[u8],
+	Staged:impl FnOnce(&Path) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+	let mut Temporary = Path.as_os_str().to_owned();
+
+	Temporary.push(format!(".tmp-{}", std::process::id()));
+
+	let Temporary = PathBuf::from(Temporary);
+
+	tokio::fs::write(&Temporary, Content).await?;
+
+	if let Err(Error) = Staged(&Temporary) {
+		let _ = tokio::fs::remove_file(&Temporary).await;
+
+		return Err(Error);
+	}
+
+	if let Err(Error) = tokio::fs::rename(&Temporary, Path).await {
+		warn!("Cannot rename {} into place: {}, writing directly.", Temporary.display(), Error);
+
+		let _ = tokio::fs::remove_file(&Temporary).await;
+
+		tokio::fs::write(Path, Content).await?;
+	}
+
+	Ok(())
+}
+
+/// Rewrites relative module specifiers to name the JavaScript emitted for them,
+/// leaving package specifiers and other extensions alone.
+///
+/// Specifiers without a TypeScript extension are only completed when a source
+/// they resolve to exists next to `Directory`, so `./c.module` becomes
+/// `./c.module.js` when `c.module.ts` is there and `./d` becomes
+/// `./d/index.js` when `d` is a directory with an index.
+struct Rewrite {
+	/// The directory of the file being compiled.
+	Directory:PathBuf,
+}
+
+impl Rewrite {
+	/// Extensions of the sources a specifier may name without one.
+	const SOURCE:[&'static str; 4] = ["ts", "tsx", "js", "jsx"];
+
+	fn specifier(&self, Specifier:&mut Str) {
+		let Value = Specifier.value.to_string();
+
+		if !Value.starts_with("./") && !Value.starts_with("../") {
+			return;
+		}
+
+		let Extension = Path::new(&Value).extension().and_then(|Extension| Extension.to_str());
+
+		let Rewritten = match Extension {
+			Some("ts") => format!("{}.js", &Value[..Value.len() - 3]),
+			Some("tsx" | "jsx") => format!("{}.js", &Value[..Value.len() - 4]),
+			Some("mts") => format!("{}.mjs", &Value[..Value.len() - 4]),
+			Some("cts") => format!("{}.cjs", &Value[..Value.len() - 4]),
+			_ => {
+				let Target = self.Directory.join(Value.trim_end_matches('/'));
+
+				let Exists = |Base:&Path| {
+					Self::SOURCE.iter().any(|Extension| {
+						let mut Name = Base.as_os_str().to_owned();
+
+						Name.push(".");
+						Name.push(Extension);
+
+						Path::new(&Name).is_file()
+					})
+				};
+
+				if !Value.ends_with('/') && Exists(&Target) {
+					format!("{}.js", Value)
+				} else if Exists(&Target.join("index")) {
+					format!("{}/index.js", Value.trim_end_matches('/'))
+				} else {
+					return;
+				}
+			},
+		};
+
+		Specifier.value = Rewritten.into();
+		Specifier.raw = None;
+	}
+}
+
+impl VisitMut for Rewrite {
+	fn visit_mut_import_decl(&mut self, Import:&mut ImportDecl) {
+		self.specifier(&mut Import.src);
+	}
+
+	fn visit_mut_named_export(&mut self, Export:&mut NamedExport) {
+		if let Some(Source) = &mut Export.src {
+			self.specifier(Source);
+		}
+	}
+
+	fn visit_mut_export_all(&mut self, Export:&mut ExportAll) { self.specifier(&mut Export.src); }
+
+	/// Dynamic `import()` and `require()` calls with a literal specifier.
+	fn visit_mut_call_expr(&mut self, Call:&mut CallExpr) {
+		Call.visit_mut_children_with(self);
+
+		let Loads = match &Call.callee {
+			Callee::Import(_) => true,
+			Callee::Expr(Callee) => Callee.as_ident().is_some_and(|Ident| &*Ident.sym == "require"),
+			Callee::Super(_) => false,
+		};
+
+		if let Some(Expr::Lit(Lit::Str(Specifier))) =
+			Call.args.first_mut().filter(|_| Loads).map(|Argument| &mut *Argument.expr)
+		{
+			self.specifier(Specifier);
+		}
+	}
+}
+
+/// The level, message and primary span of one reported diagnostic.
+type Diagnostic = (Level, String, std::option::Option<Span>);
+
+/// Collects the diagnostics the transforms report through `HANDLER`.
+#[derive(Clone, Default)]
+struct Diagnostics(Arc<std::sync::Mutex<Vec<Diagnostic>>>);
+
+impl Diagnostics {
+	/// Drains the diagnostics into the errors and the warnings, each located in
+	/// `File` through `cm`.
+	fn take(&self, cm:&SourceMap, File:&str) -> (Vec<String>, Vec<String>) {
+		let mut Error = Vec::new();
+
+		let mut Warning = Vec::new();
+
+		let Entry = self.0.lock().map(|mut Entry| std::mem::take(&mut *Entry)).unwrap_or_default();
+
+		for (Level, Message, Span) in Entry {
+			let Message = match Span {
+				Some(Span) if !Span.is_dummy() => Locate(cm, File, Span, Message),
+				_ => format!("{}: {}", File, Message),
+			};
+
+			match Level {
+				Level::Bug | Level::Fatal | Level::PhaseFatal | Level::Error => Error.push(Message),
+				Level::Warning => Warning.push(Message),
+				_ => {},
+			}
+		}
+
+		(Error, Warning)
+	}
+}
+
+impl swc_common::errors::Emitter for Diagnostics {
+	fn emit(&mut self, Diagnostic:&DiagnosticBuilder<'_>) {
+		if let Ok(mut Entry) = self.0.lock() {
+			Entry.push((Diagnostic.level, Diagnostic.message(), Diagnostic.span.primary_span()));
+		}
+	}
+}
+
+/// Replaces the field of `Value` whose lowercased name is `Field`, or that of a
+/// nested object prefixed by its name, with `Raw`. Returns whether a field
+/// matched.
+fn Overlay(Value:&mut serde_json::Value, Field:&str, Raw:&str) -> bool {
+	let Some(Object) = Value.as_object_mut() else {
+		return false;
+	};
+
+	for (Name, Slot) in Object.iter_mut() {
+		let Name = Name.to_lowercase();
+
+		if Name == Field {
+			*Slot = match Slot {
+				serde_json::Value::String(_) | serde_json::Value::Null => Raw.into(),
+				_ => serde_json::from_str(Raw).unwrap_or_else(|_| Raw.into()),
+			};
+
+			return true;
+		}
+
+		if let Some(Nested) = Field.strip_prefix(&Name) {
+			if Slot.is_object() && Overlay(Slot, Nested, Raw) {
+				return true;
+			}
+		}
+	}
+
+	false
+}
+
+/// Formats `Message` as `File:line:col: Message` followed by the offending
+/// source line and a caret under the start of `Span`.
+fn Locate(cm:&SourceMap, File:&str, Span:Span, Message:impl std::fmt::Display) -> String {
+	let Location = cm.lookup_char_pos(Span.lo);
+
+	let Line = Location.file.get_line(Location.line - 1).unwrap_or_default();
+
+	format!(
+		"{}:{}:{}: {}\n{}\n{}^",
+		File,
+		Location.line,
+		Location.col_display + 1,
+		Message,
+		Line.trim_end(),
+		" ".repeat(Location.col_display)
+	)
+}
+
+/// Builds the path of `To` as seen from the directory `From`, ignoring `.`
+/// components so `./src` and `src` compare equal.
+fn Relative(From:&Path, To:&Path) -> PathBuf {
+	let From = From.components().filter(|Part| *Part != Component::CurDir).collect::<Vec<_>>();
+
+	let To = To.components().filter(|Part| *Part != Component::CurDir).collect::<Vec<_>>();
+
+	let Common = From.iter().zip(To.iter()).take_while(|(From, To)| From == To).count();
+
+	From[Common..]
+		.iter()
+		.map(|_| Component::ParentDir)
+		.chain(To[Common..].iter().copied())
+		.collect()
+}
+
+use std::{
+	collections::HashMap,
+	ffi::OsString,
+	path::{Component, Path, PathBuf},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	time::{Duration, Instant, SystemTime},
+};
+
+use anyhow::{anyhow, Context, Result};
+use dashmap::DashMap;
+use globset::{Glob, GlobMatcher, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use swc_common::{
+	comments::{Comments, SingleThreadedComments},
+	errors::{DiagnosticBuilder, Handler, Level, HANDLER},
+	sync::Lrc,
+	FileName, Globals, Mark, SourceMap, Span, Spanned, GLOBALS,
+};
+use swc_ecma_ast::{
+	CallExpr, Callee, EsVersion, ExportAll, Expr, ImportDecl, Lit, Module, ModuleItem,
+	NamedExport, Program, Str,
+};
+use swc_ecma_codegen::{text_writer::JsWriter, Emitter};
+use swc_ecma_minifier::option::{CompressOptions, ExtraOptions, MinifyOptions, TopLevelOptions};
+use swc_ecma_parser::{lexer::Lexer, EsSyntax, Parser, StringInput, Syntax, TsSyntax};
+use swc_ecma_transforms_base::{
+	feature::enable_available_feature_from_es_version,
+	helpers::{inject_helpers, Helpers, HELPERS},
+};
+use swc_ecma_transforms_module::path::Resolver;
+use swc_ecma_transforms_proposal::decorators;
+use swc_ecma_transforms_react::Runtime;
+use swc_ecma_visit::{FoldWith, VisitMut, VisitMutWith};
+use swc_typescript::fast_dts::FastDts;
+use tokio::sync::Mutex;
+use tracing::{trace, warn};
+
+#[cfg(test)]
+mod Test {
+	#[tokio::test]
+	async fn WriteReplaces() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join("a.js");
+
+		std::fs::write(&File, "original").unwrap();
+
+		Write(&File, b"compiled").await.unwrap();
+
+		assert_eq!(std::fs::read_to_string(&File).unwrap(), "compiled");
+
+		assert_eq!(std::fs::read_dir(Directory.path()).unwrap().count(), 1);
+	}
+
+	#[tokio::test]
+	async fn ReplaceKeepsOriginalWhenInterrupted() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join("a.js");
+
+		std::fs::write(&File, "original").unwrap();
+
+		let Result =
+			Replace(&File, b"compiled", |_| Err(std::io::Error::other("Interrupted"))).await;
+
+		assert!(Result.is_err());
+
+		assert_eq!(std::fs::read_to_string(&File).unwrap(), "original");
+
+		assert_eq!(std::fs::read_dir(Directory.path()).unwrap().count(), 1);
+	}
+
+	#[tokio::test]
+	async fn CacheSaveMerges() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join(Cache::PATH);
+
+		let (First, Second) = (Cache::load(&File).await, Cache::load(&File).await);
+
+		let Now = SystemTime::now();
+
+		First.update("a.ts".into(), Now, Cache::hash("a"));
+
+		Second.update("b.ts".into(), Now, Cache::hash("b"));
+
+		First.save(&File).await.unwrap();
+
+		Second.save(&File).await.unwrap();
+
+		let Saved = Cache::load(&File).await;
+
+		assert!(Saved.is_same(Path::new("a.ts"), &Cache::hash("a")));
+
+		assert!(Saved.is_same(Path::new("b.ts"), &Cache::hash("b")));
+	}
+
+	#[tokio::test]
+	async fn EnvOverridesFile() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join(CompilerConfig::PATH);
+
+		std::fs::write(&File, r#"{ "Target": "es2020", "Minify": true }"#).unwrap();
+
+		let Variable = [("REST_TARGET".into(), "es2018".into()), ("PATH".into(), "/bin".into())];
+
+		let Config = CompilerConfig::load(&File).await.unwrap().overlay(Variable).unwrap();
+
+		assert_eq!(Config.Target, "es2018");
+
+		assert!(Config.Minify);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn EnvSkipsInvalidUnicode() {
+		use std::os::unix::ffi::OsStringExt;
+
+		let Config = CompilerConfig::default()
+			.overlay([
+				("REST_MODULE".into(), OsString::from_vec(vec![0xff])),
+				(OsString::from_vec(vec![0xff]), "es6".into()),
+			])
+			.unwrap();
+
+		assert_eq!(Config.Module, "commonjs");
+	}
+
+	#[tokio::test]
+	async fn CompileFileErrorsByKind() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Blocked = Directory.path().join("blocked");
+
+		std::fs::write(&Blocked, "").unwrap();
+
+		let Compiler = Compiler::new(CompilerConfig {
+			OutDir:Some(Blocked.join("out")),
+			Root:Directory.path().to_path_buf(),
+			..Default::default()
+		});
+
+		let File = Directory.path().join("a.ts").to_string_lossy().to_string();
+
+		assert!(matches!(
+			Compiler.compile_file(&File, "const x = ;".to_string()).await,
+			Err(CompilerError::Parse(_))
+		));
+
+		assert!(matches!(
+			Compiler.compile_file(&File, "const x = 1;".to_string()).await,
+			Err(CompilerError::Io(_))
+		));
+	}
+
+	#[test]
+	fn RewriteImportsNamesJavaScript() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		for File in ["a.ts", "c.module.ts", "d/index.ts", "e.tsx", "f.ts"] {
+			let File = Directory.path().join(File);
+
+			std::fs::create_dir_all(File.parent().unwrap()).unwrap();
+
+			std::fs::write(File, "export default 1;").unwrap();
+		}
+
+		let Compiler = Compiler::new(CompilerConfig {
+			Module:"es6".to_string(),
+			RewriteImports:true,
+			..Default::default()
+		});
+
+		let Source = [
+			"import a from \"./a.ts\";",
+			"import b from \"./b\";",
+			"import c from \"./c.module\";",
+			"import d from \"./d\";",
+			"import React from \"react\";",
+			"export { a, b, c, d, React };",
+			"export const e = import(\"./e\");",
+			"export const f = require(\"./f\");",
+		];
+
+		let Output = Compiler
+			.compile_str(&Directory.path().join("main.ts").to_string_lossy(), Source.join("\n"))
+			.unwrap()
+			.code;
+
+		for Expected in [
+			"from \"./a.js\"",
+			"from \"./b\"",
+			"from \"./c.module.js\"",
+			"from \"./d/index.js\"",
+			"from \"react\"",
+			"import(\"./e.js\")",
+			"require(\"./f.js\")",
+		] {
+			assert!(Output.contains(Expected), "{} missing from {}", Expected, Output);
+		}
+	}
+
+	#[tokio::test]
+	async fn MalformedSourceIsAnError() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join("a.ts");
+
+		let Result = Compiler::new(CompilerConfig::default())
+			.compile_file(&File.to_string_lossy(), "const x = ;".to_string())
+			.await;
+
+		assert!(Result.unwrap_err().to_string().contains("a.ts"));
+
+		assert!(!File.with_extension("js").exists());
+	}
+
+	#[test]
+	fn ParseTargetMapsVersions() {
+		assert_eq!(parse_target("es5").unwrap(), EsVersion::Es5);
+
+		assert_eq!(parse_target("ESNext").unwrap(), EsVersion::EsNext);
+
+		assert!(parse_target("bogus").is_err());
+	}
+
+	#[test]
+	fn ModuleSelectsOutput() {
+		let Compile = |Module:&str| {
+			Compiler::new(CompilerConfig { Module:Module.to_string(), ..Default::default() })
+				.compile_str("a.ts", "export const x = 1;".to_string())
+				.unwrap()
+				.code
+		};
+
+		// SWC exports through a getter rather than assigning `exports.x`.
+		assert!(Compile("commonjs").contains(r#"Object.defineProperty(exports, "x""#));
+
+		assert!(Compile("es6").contains("export const x = 1;"));
+
+		assert!(Compile("amd").contains("define("));
+	}
+
+	#[test]
+	fn TsxCompilesJsx() {
+		let Compile = |JsxRuntime:&str| {
+			let Config = CompilerConfig { JsxRuntime:JsxRuntime.to_string(), ..Default::default() };
+
+			Compiler::new(Config)
+				.compile_str("a.tsx", "export const a = <div/>;".to_string())
+				.unwrap()
+				.code
+		};
+
+		assert!(Compile("classic").contains("React.createElement(\"div\""));
+
+		assert!(Compile("automatic").contains("react/jsx-runtime"));
+
+		assert!(Compiler::new(CompilerConfig::default())
+			.compile_str("a.ts", "export const a = <div/>;".to_string())
+			.is_err());
+	}
+
+	#[test]
+	fn MinifyShortens() {
+		let Source = [
+			"export function f(value: number) {",
+			"\tif (false) {",
+			"\t\tconsole.log(value);",
+			"\t}",
+			"\treturn value * 2;",
+			"}",
+		]
+		.join("\n");
+
+		let Compile = |Minify:bool| {
+			Compiler::new(CompilerConfig { Minify, ..Default::default() })
+				.compile_str("a.ts", Source.clone())
+				.unwrap()
+				.code
+		};
+
+		let (Minified, Pretty) = (Compile(true), Compile(false));
+
+		assert!(Minified.len() < Pretty.len());
+
+		assert!(!Minified.contains("console.log"));
+
+		assert!(Pretty.contains("console.log(value);"));
+	}
+
+	#[tokio::test]
+	async fn CompileFileWritesModule() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join("a.ts");
+
+		let Output = Compiler::new(CompilerConfig::default())
+			.compile_file(&File.to_string_lossy(), "export const a: number = 1;".to_string())
+			.await
+			.unwrap();
+
+		assert_eq!(Output.path, File.with_extension("js").to_string_lossy());
+
+		let Written = std::fs::read_to_string(File.with_extension("js")).unwrap();
+
+		assert!(Written.contains("const a = 1;"), "{}", Written);
+
+		assert!(!Written.contains("number"), "{}", Written);
+	}
+
+	#[tokio::test]
+	async fn DurationsArePerFile() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Compiler = Compiler::new(CompilerConfig::default());
+
+		let (Small, Large) = (Directory.path().join("small.ts"), Directory.path().join("large.ts"));
+
+		Compiler.compile_file(&Small.to_string_lossy(), "export {};".to_string()).await.unwrap();
+
+		let Source = (0..500)
+			.map(|Index| format!("export const a{}: number = {};\n", Index, Index))
+			.collect();
+
+		Compiler.compile_file(&Large.to_string_lossy(), Source).await.unwrap();
+
+		let Outlook = Compiler.Outlook.lock().await;
+
+		assert_eq!(Outlook.File.len(), 2);
+
+		assert!(Outlook.File.contains_key(&Small));
+
+		assert!(Outlook.File.contains_key(&Large));
+
+		assert_eq!(Outlook.slowest(1).len(), 1);
+
+		assert_eq!(Outlook.slowest(10).len(), 2);
+	}
+
+	#[test]
+	fn PatternGlobAndSuffix() {
+		let Glob = Pattern::new("src/**/*.ts").unwrap();
+
+		assert!(matches!(Glob, Pattern::Glob(_)));
+
+		assert!(Glob.is_match(Path::new("./src/deep/a.ts")));
+
+		assert!(!Glob.is_match(Path::new("lib/a.ts")));
+
+		assert!(Pattern::new("*.ts").unwrap().is_match(Path::new("a.ts")));
+
+		let Suffix = Pattern::new(".ts").unwrap();
+
+		assert!(matches!(Suffix, Pattern::Suffix(_)));
+
+		assert!(Suffix.is_match(Path::new("lib/a.ts")));
+
+		assert!(!Suffix.is_match(Path::new("lib/a.tsx")));
+
+		let Entry = Patterns::new(&["*.ts".to_string(), ".tsx".to_string()]).unwrap();
+
+		assert!(Entry.is_match(Path::new("a.ts")) && Entry.is_match(Path::new("b.tsx")));
+
+		assert!(!Entry.is_match(Path::new("a.d.ts")));
+	}
+
+	#[tokio::test]
+	async fn TsconfigMapsCompilerOptions() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join(CompilerConfig::TSCONFIG);
+
+		std::fs::write(
+			&File,
+			r#"{
+				"compilerOptions": {
+					"target": "ES2017",
+					"module": "ESNext",
+					"strict": false,
+					"experimentalDecorators": true,
+					"emitDecoratorMetadata": false,
+					"jsx": "react-jsx"
+				}
+			}"#,
+		)
+		.unwrap();
+
+		let Config = CompilerConfig::from_path(&File).await.unwrap();
+
+		assert_eq!(Config.Target, "es2017");
+
+		assert_eq!(Config.Module, "es6");
+
+		assert!(!Config.Strict);
+
+		assert!(Config.LegacyDecorators);
+
+		assert!(!Config.EmitDecoratorsMetadata);
+
+		assert_eq!(Config.JsxRuntime, CompilerConfig::default().JsxRuntime);
+	}
+
+	#[test]
+	fn PreserveCommentsKeepsThem() {
+		let Compile = |PreserveComments:bool| {
+			Compiler::new(CompilerConfig { PreserveComments, ..Default::default() })
+				.compile_str("a.ts", "// keep me\nexport const a = 1; /* and me */\n".to_string())
+				.unwrap()
+				.code
+		};
+
+		let Kept = Compile(true);
+
+		assert!(Kept.contains("// keep me") && Kept.contains("/* and me */"), "{}", Kept);
+
+		assert!(!Compile(false).contains("keep me"));
+	}
+
+	#[tokio::test]
+	async fn DeclarationsForExportsOnly() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Compiler =
+			Compiler::new(CompilerConfig { EmitDeclarations:true, ..Default::default() });
+
+		let (Exported, Private) = (Directory.path().join("a.ts"), Directory.path().join("b.ts"));
+
+		Compiler
+			.compile_file(
+				&Exported.to_string_lossy(),
+				"export function f(x: number): string { return \"\"; }".to_string(),
+			)
+			.await
+			.unwrap();
+
+		Compiler
+			.compile_file(&Private.to_string_lossy(), "const b = 1;".to_string())
+			.await
+			.unwrap();
+
+		let Declaration = std::fs::read_to_string(Exported.with_extension("d.ts")).unwrap();
+
+		assert!(
+			Declaration.contains("export declare function f(x: number): string;"),
+			"{}",
+			Declaration
+		);
+
+		assert!(!Private.with_extension("d.ts").exists());
+	}
+
+	#[test]
+	fn LegacyDecoratorsEmitMetadata() {
+		let Source = [
+			"function Component(Target: any) {}",
+			"function Input(Target: any, Key: string) {}",
+			"@Component",
+			"export class A {",
+			"\t@Input name: string = \"a\";",
+			"}",
+		]
+		.join("\n");
+
+		let Compile = |LegacyDecorators:bool| {
+			Compiler::new(CompilerConfig { LegacyDecorators, ..Default::default() })
+				.compile_str("a.ts", Source.clone())
+				.unwrap()
+				.code
+		};
+
+		let (Legacy, Modern) = (Compile(true), Compile(false));
+
+		assert!(Legacy.contains(r#"_ts_metadata("design:type", String)"#), "{}", Legacy);
+
+		assert!(Legacy.contains("_ts_decorate(["), "{}", Legacy);
+
+		assert!(!Modern.contains("design:type"));
+
+		assert!(Modern.contains("_decorate(["));
+	}
+
+	#[test]
+	fn ParseErrorNamesLine() {
+		let Error = Compiler::new(CompilerConfig::default())
+			.compile_str("a.ts", "const a = 1;\nconst b = 2;\nconst c = ;\n".to_string())
+			.unwrap_err()
+			.to_string();
+
+		assert!(Error.contains("a.ts:3:"), "{}", Error);
+
+		assert!(Error.contains("const c = ;"), "{}", Error);
+	}
+
+	#[tokio::test]
+	async fn InlineSourceMapWritesNoFile() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join("a.ts");
+
+		Compiler::new(CompilerConfig { InlineSourceMap:true, ..Default::default() })
+			.compile_file(&File.to_string_lossy(), "export const a: number = 1;".to_string())
+			.await
+			.unwrap();
+
+		let Output = std::fs::read_to_string(File.with_extension("js")).unwrap();
+
+		assert!(Output.contains("//# sourceMappingURL=data:application/json;"), "{}", Output);
+
+		assert!(Output.contains(";base64,"), "{}", Output);
+
+		assert!(!File.with_extension("js.map").exists());
+
+		assert!(CompilerConfig { InlineSourceMap:true, SourceMaps:true, ..Default::default() }
+			.validate()
+			.is_err());
+	}
+
+	#[test]
+	fn TsSyntaxChangesParse() {
+		let Compile = |Ts:TsSyntaxConfig, Source:&str| {
+			Compiler::new(CompilerConfig { Ts, ..Default::default() })
+				.compile_str("a.ts", Source.to_string())
+		};
+
+		let Assertion = "export const a = <number>1;";
+
+		assert!(Compile(TsSyntaxConfig::default(), Assertion).is_ok());
+
+		let Strict = TsSyntaxConfig { DisallowAmbiguousJsxLike:true, ..Default::default() };
+
+		assert!(Compile(Strict, Assertion).is_err());
+
+		let Decorated = "function d(Target: any) {}\n@d\nexport class A {}";
+
+		assert!(Compile(TsSyntaxConfig::default(), Decorated).is_ok());
+
+		let Plain = TsSyntaxConfig { Decorators:false, ..Default::default() };
+
+		assert!(Compile(Plain, Decorated).is_err());
+	}
+
+	#[tokio::test]
+	async fn ByteOrderMarkIsDropped() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join("a.ts");
+
+		let Source = "\u{feff}export const a: number = 1;\n";
+
+		std::fs::write(&File, Source).unwrap();
+
+		#[cfg(unix)]
+		std::fs::set_permissions(&File, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+		Compiler::new(CompilerConfig::default())
+			.compile_file(&File.to_string_lossy(), Source.to_string())
+			.await
+			.unwrap();
+
+		let Written = std::fs::read_to_string(File.with_extension("js")).unwrap();
+
+		assert!(!Written.contains('\u{feff}'), "{:?}", Written);
+
+		assert!(Written.contains("const a = 1;"), "{}", Written);
+
+		#[cfg(unix)]
+		assert_eq!(
+			std::fs::metadata(File.with_extension("js")).unwrap().permissions().mode() & 0o777,
+			0o755
+		);
+	}
+
+	/// Keeps the fields of every span opened while it is the subscriber.
+	struct Capture(Arc<std::sync::Mutex<Vec<(String, String)>>>);
+
+	impl<Subscriber:tracing::Subscriber> Layer<Subscriber> for Capture {
+		fn on_new_span(&self, Attribute:&Attributes<'_>, _:&Id, _:Context<'_, Subscriber>) {
+			Attribute.record(&mut |Field:&Field, Value:&dyn std::fmt::Debug| {
+				self.0.lock().unwrap().push((Field.name().to_string(), format!("{:?}", Value)));
+			});
+		}
+	}
+
+	#[tokio::test]
+	async fn SpanRecordsFile() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join("a.ts").to_string_lossy().to_string();
+
+		let Field = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+		let _Guard = tracing::subscriber::set_default(
+			tracing_subscriber::registry().with(Capture(Arc::clone(&Field))),
+		);
+
+		Compiler::new(CompilerConfig::default())
+			.compile_file(&File, "export const a: number = 1;".to_string())
+			.await
+			.unwrap();
+
+		let Field = Field.lock().unwrap();
+
+		assert!(Field.contains(&("file".to_string(), File)), "{:?}", Field);
+
+		assert!(Field.contains(&("bytes".to_string(), "27".to_string())), "{:?}", Field);
+	}
+
+	#[test]
+	fn WarningsAreKeptApartFromErrors() {
+		let cm:Lrc<SourceMap> = Default::default();
+
+		let Source = cm.new_source_file(
+			Lrc::new(FileName::Real("a.ts".into())),
+			"const a = 1;\nconst b = 2;\n".to_string(),
+		);
+
+		let Buffer = Diagnostics::default();
+
+		let Handler = Handler::with_emitter(true, false, Box::new(Buffer.clone()));
+
+		let Second = Source.start_pos + swc_common::BytePos(13);
+
+		Handler.struct_span_warn(Span::new(Second, Second), "Unused b").emit();
+
+		Handler.warn("Unlocated");
+
+		let (Error, Warning) = Buffer.take(&cm, "a.ts");
+
+		assert!(Error.is_empty(), "{:?}", Error);
+
+		assert_eq!(Warning.len(), 2);
+
+		assert!(Warning[0].starts_with("a.ts:2:1: Unused b\nconst b = 2;"), "{}", Warning[0]);
+
+		assert_eq!(Warning[1], "a.ts: Unlocated");
+
+		Handler.err("Broken");
+
+		assert_eq!(Buffer.take(&cm, "a.ts"), (vec!["a.ts: Broken".to_string()], Vec::new()));
+	}
+
+	#[test]
+	fn AsciiOnlyEscapesEmoji() {
+		let Compile = |AsciiOnly:bool| {
+			Compiler::new(CompilerConfig { AsciiOnly, ..Default::default() })
+				.compile_str("a.ts", "export const face: string = \"smile 😀\";".to_string())
+				.unwrap()
+				.code
+		};
+
+		let (Escaped, Verbatim) = (Compile(true), Compile(false));
+
+		assert!(Escaped.is_ascii(), "{}", Escaped);
+
+		assert!(Escaped.contains("smile \\u"), "{}", Escaped);
+
+		assert!(Verbatim.contains("smile 😀"), "{}", Verbatim);
+	}
+
+	#[tokio::test]
+	async fn CompileStrCreatesNoFile() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let File = Directory.path().join("a.ts");
+
+		let Config = CompilerConfig { SourceMaps:true, ..Default::default() };
+
+		let Compiler = Compiler::new(Config);
+
+		let Output = Compiler
+			.compile_str(&File.to_string_lossy(), "export const a: number = 1;".to_string())
+			.unwrap();
+
+		assert!(Output.code.contains("const a = 1;"), "{}", Output.code);
+
+		assert!(Output.map.is_some_and(|Map| Map.contains("\"mappings\"")));
+
+		assert_eq!(std::fs::read_dir(Directory.path()).unwrap().count(), 0);
+
+		assert_eq!(Compiler.Outlook.lock().await.Count, 0);
+	}
+
+	#[test]
+	fn ValidateRejectsContradictions() {
+		let Config = |Target:&str, Module:&str| CompilerConfig {
+			Target:Target.to_string(),
+			Module:Module.to_string(),
+			..Default::default()
+		};
+
+		let Rejected = Config("es3", "esm").validate().unwrap_err();
+
+		assert!(format!("{:#}", Rejected).contains("Target es3 cannot run"), "{:#}", Rejected);
+
+		assert!(Config("es3", "commonjs").validate().is_ok());
+
+		assert!(Config("es5", "es6").validate().is_ok());
+
+		assert!(Config("es2020", "esm").validate().is_ok());
+
+		assert!(Config("es2020", "umd").validate().is_err());
+	}
+
+	#[tokio::test]
+	async fn EmptySourceCompilesToNothing() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		for SkipEmpty in [false, true] {
+			let File = Directory.path().join(format!("{}.ts", SkipEmpty));
+
+			let Compiler = Compiler::new(CompilerConfig { SkipEmpty, ..Default::default() });
+
+			Compiler.compile_file(&File.to_string_lossy(), " \n\t\n".to_string()).await.unwrap();
+
+			assert_eq!(Compiler.Outlook.lock().await.Count, 1);
+
+			let Written = std::fs::read_to_string(File.with_extension("js")).ok();
+
+			assert_eq!(Written, (!SkipEmpty).then(String::new));
+		}
+	}
+
+	#[tokio::test]
+	async fn NearestConfigPerDirectory() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Compiler = Compiler::new(CompilerConfig::default());
+
+		let mut Output = Vec::new();
+
+		for (Package, Target) in [("legacy", "es5"), ("modern", "es2020")] {
+			let Package = Directory.path().join(Package);
+
+			std::fs::create_dir_all(Package.join("src")).unwrap();
+
+			std::fs::write(
+				Package.join(CompilerConfig::PATH),
+				format!(r#"{{ "Target": "{}", "AsciiOnly": true }}"#, Target),
+			)
+			.unwrap();
+
+			let File = Package.join("src/a.ts");
+
+			Compiler
+				.compile_file(&File.to_string_lossy(), "export const f = \"😀\";".to_string())
+				.await
+				.unwrap();
+
+			Output.push(std::fs::read_to_string(File.with_extension("js")).unwrap());
+		}
+
+		// Code points past the BMP are escaped either way depending on the target.
+		assert!(Output[0].contains(r#""\uD83D\uDE00""#), "{}", Output[0]);
+
+		assert!(Output[1].contains(r#""\u{1F600}""#), "{}", Output[1]);
+	}
+
+	#[test]
+	fn TreeShakeDropsUnusedLocals() {
+		let Source = [
+			"function unused() { return 1; }",
+			"function helper() { return 2; }",
+			"export function used() { return helper(); }",
+			"export const value: number = 3;",
+		]
+		.join("\n");
+
+		let Compile = |TreeShake:bool| {
+			let Module = "es6".to_string();
+
+			Compiler::new(CompilerConfig { TreeShake, Module, ..Default::default() })
+				.compile_str("a.ts", Source.clone())
+				.unwrap()
+				.code
+		};
+
+		let (Shaken, Kept) = (Compile(true), Compile(false));
+
+		assert!(!Shaken.contains("unused"), "{}", Shaken);
+
+		assert!(Shaken.contains("export function used"), "{}", Shaken);
+
+		assert!(Shaken.contains("value"), "{}", Shaken);
+
+		assert!(Kept.contains("function unused"), "{}", Kept);
+	}
+
+	#[test]
+	fn CompileVfsKeysEveryFile() {
+		let Directory = tempfile::tempdir().unwrap();
+
+		let Path = |File:&str| Directory.path().join(File).to_string_lossy().to_string();
+
+		let Files = HashMap::from([
+			(Path("a.ts"), "export const a: number = 1;".to_string()),
+			(Path("b.tsx"), "export const b = <div />;".to_string()),
+			(Path("c.ts"), "const x = ;".to_string()),
+		]);
+
+		let Output = Compiler::new(CompilerConfig::default()).compile_vfs(Files);
+
+		assert_eq!(Output.len(), 3);
+
+		assert!(Output[&Path("a.ts")].as_ref().unwrap().code.contains("const a = 1;"));
+
+		assert!(Output[&Path("b.tsx")].as_ref().unwrap().code.contains("createElement"));
+
+		assert!(matches!(Output[&Path("c.ts")], Err(CompilerError::Parse(_))));
+
+		assert_eq!(std::fs::read_dir(Directory.path()).unwrap().count(), 0);
+	}
+
+	#[test]
+	fn StrictTogglesPrologue() {
+		let Compile = |Strict:bool, Module:&str| {
+			let Module = Module.to_string();
+
+			Compiler::new(CompilerConfig { Strict, Module, ..Default::default() })
+				.compile_str("a.ts", "export const a: number = 1;".to_string())
+				.unwrap()
+				.code
+		};
+
+		assert!(Compile(true, "commonjs").starts_with("\"use strict\";"));
+
+		assert!(!Compile(false, "commonjs").contains("use strict"));
+
+		assert!(Compile(true, "amd").contains("\"use strict\";"));
+
+		assert!(!Compile(true, "es6").contains("use strict"));
+	}
+
+	#[test]
+	fn JsxIsParsedAsJavaScript() {
+		let Compile = |File:&str, Source:&str| {
+			Compiler::new(CompilerConfig::default()).compile_str(File, Source.to_string())
+		};
+
+		let Source = r#"export const App = () => <div className="app" />;"#;
+
+		let Output = Compile("a.jsx", Source).unwrap();
+
+		assert!(Output.code.contains("createElement(\"div\""), "{}", Output.code);
+
+		assert!(Compile("a.js", "export const a = 1;").unwrap().code.contains("const a = 1;"));
+
+		assert!(matches!(Compile("a.js", "const a: number = 1;"), Err(CompilerError::Parse(_))));
+	}
+
+	#[cfg(unix)]
+	use std::os::unix::fs::PermissionsExt;
+
+	use tracing::{
+		field::Field,
+		span::{Attributes, Id},
+	};
+	use tracing_subscriber::{
+		layer::{Context, SubscriberExt},
+		Layer,
+	};
+
+	use super::*;
+}
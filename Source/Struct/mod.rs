@@ -1,2 +1,3 @@
 pub mod Binary;
+pub mod Build;
 pub mod SWC;
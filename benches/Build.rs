@@ -0,0 +1,91 @@
+#![allow(non_snake_case)]
+
+//! Measures `Build::Fn` walking a generated repository, with the diffing
+//! capped to one thread and spread over rayon's global pool.
+
+/// How many commits the fixture repository holds.
+const COMMIT:usize = 200;
+
+/// Builds a repository of `COMMIT` commits, each touching one of a handful of
+/// files so every diff has a parent to compare against.
+fn Fixture() -> TempDir {
+	let Directory = TempDir::new().expect("Cannot tempdir.");
+
+	let Repository = Repository::init(Directory.path()).expect("Cannot init.");
+
+	let mut Parent = None::<Oid>;
+
+	for Index in 0..COMMIT {
+		std::fs::write(
+			Directory.path().join(format!("{}.txt", Index % 8)),
+			format!("line {}\n", Index).repeat(Index % 16 + 1),
+		)
+		.expect("Cannot write.");
+
+		let mut Staged = Repository.index().expect("Cannot index.");
+
+		Staged.add_all(["*"], IndexAddOption::DEFAULT, None).expect("Cannot add_all.");
+
+		Staged.write().expect("Cannot write index.");
+
+		let Tree = Repository
+			.find_tree(Staged.write_tree().expect("Cannot write_tree."))
+			.expect("Cannot find_tree.");
+
+		let Author = Signature::new("Ann", "ann@example.com", &Time::new(Index as i64, 0))
+			.expect("Cannot sign.");
+
+		let Previous = Parent.map(|Oid| Repository.find_commit(Oid).expect("Cannot find_commit."));
+
+		Parent = Some(
+			Repository
+				.commit(
+					Some("HEAD"),
+					&Author,
+					&Author,
+					&format!("change {}", Index),
+					&Tree,
+					&Previous.iter().collect::<Vec<_>>(),
+				)
+				.expect("Cannot commit."),
+		);
+	}
+
+	Directory
+}
+
+fn Build(Criterion:&mut Criterion) {
+	let Directory = Fixture();
+
+	let Entry = Directory.path().to_string_lossy().to_string();
+
+	let Runtime = Runtime::new().expect("Cannot start the runtime.");
+
+	let mut Group = Criterion.benchmark_group("Build::Fn");
+
+	Group.throughput(Throughput::Elements(COMMIT as u64));
+
+	for (Name, Threads) in [("1", Some(1)), ("default", None)] {
+		let Option = Option { Threads, ..Option::default() };
+
+		Group.bench_with_input(BenchmarkId::new("Threads", Name), &Option, |Bencher, Option| {
+			Bencher.iter(|| {
+				Runtime.block_on(Fn(black_box(&Entry), Option)).expect("Cannot build.")
+			})
+		});
+	}
+
+	Group.finish();
+}
+
+criterion_group!(Benchmark, Build);
+
+criterion_main!(Benchmark);
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use git2::{IndexAddOption, Oid, Repository, Signature, Time};
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+use Library::{Fn::Build::Fn, Struct::Build::Struct as Option};
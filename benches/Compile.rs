@@ -0,0 +1,146 @@
+#![allow(non_snake_case)]
+
+//! Measures `Compiler::compile_str` on generated TypeScript of growing size,
+//! entirely in memory, and `Compile::Fn` on a directory of such files with
+//! growing `batch_size`.
+
+/// One unit of the fixture, declaring an interface, an enum, a generic class
+/// and an async function, with `$N` replaced so every unit is distinct.
+const UNIT:&str = r#"
+export interface Shape$N {
+	readonly id: number;
+	name?: string;
+	tags: Array<string>;
+}
+
+export enum Kind$N {
+	Circle = "circle",
+	Square = "square",
+}
+
+export class Store$N<T extends Shape$N> {
+	private items: Map<number, T> = new Map();
+
+	constructor(private readonly limit: number = 16) {}
+
+	add(item: T): boolean {
+		if (this.items.size >= this.limit) {
+			return false;
+		}
+
+		this.items.set(item.id, { ...item, name: item.name ?? `shape-${item.id}` });
+
+		return true;
+	}
+
+	get size(): number {
+		return this.items.size;
+	}
+}
+
+export async function load$N(kind: Kind$N, ids: number[]): Promise<Shape$N[]> {
+	const shapes = await Promise.all(ids.map(async (id) => ({ id, tags: [kind] as string[] })));
+
+	return shapes.filter((shape): shape is Shape$N => shape.id % 2 === 0);
+}
+"#;
+
+/// Builds a fixture of `Count` units.
+fn Fixture(Count:usize) -> String {
+	(0..Count).map(|Index| UNIT.replace("$N", &Index.to_string())).collect()
+}
+
+fn Compile(Criterion:&mut Criterion) {
+	let Compiler = Compiler::new(CompilerConfig::default());
+
+	let mut Group = Criterion.benchmark_group("compile_str");
+
+	for Count in [1, 10, 100] {
+		let Source = Fixture(Count);
+
+		Group.throughput(Throughput::Bytes(Source.len() as u64));
+
+		Group.bench_with_input(BenchmarkId::from_parameter(Count), &Source, |Bencher, Source| {
+			Bencher.iter(|| {
+				Compiler.compile_str("Fixture.ts", black_box(Source.clone())).expect("Cannot compile.")
+			})
+		});
+	}
+
+	Group.finish();
+}
+
+/// How many files the `batch_size` fixture directory holds.
+const FILE:usize = 64;
+
+/// An `Option` recompiling every file under `Directory` on each run, quietly
+/// and with `Batch` files per spawned task.
+fn Project(Directory:&Path, Batch:usize) -> Option {
+	Option {
+		entry:vec![vec![Directory.to_string_lossy().to_string()]],
+		patterns:vec![".ts".to_string()],
+		config:CompilerConfig::default(),
+		config_path:None,
+		force:true,
+		debounce_ms:150,
+		ignore:Vec::new(),
+		concurrency:4,
+		batch_size:Batch,
+		report_path:None,
+		dry_run:false,
+		on_compiled:None,
+		quiet:true,
+		retry_reads:false,
+		exclude_tests:false,
+		test_patterns:Vec::new(),
+		metrics:None,
+		metrics_flush_secs:0,
+		fail_fast:false,
+		per_file_timeout_secs:0,
+		bundle:None,
+		cache_path:Directory.join(Cache::PATH),
+	}
+}
+
+fn Batch(Criterion:&mut Criterion) {
+	let Directory = TempDir::new().expect("Cannot tempdir.");
+
+	for Index in 0..FILE {
+		std::fs::write(Directory.path().join(format!("{}.ts", Index)), Fixture(1))
+			.expect("Cannot write.");
+	}
+
+	let Runtime = Runtime::new().expect("Cannot start the runtime.");
+
+	let mut Group = Criterion.benchmark_group("batch_size");
+
+	Group.throughput(Throughput::Elements(FILE as u64));
+
+	for Batch in [1, 16] {
+		let Option = Project(Directory.path(), Batch);
+
+		Group.bench_with_input(BenchmarkId::from_parameter(Batch), &Option, |Bencher, Option| {
+			Bencher.iter(|| {
+				let Run = Runtime.block_on(Fn(black_box(Option.clone()))).expect("Cannot compile.");
+
+				assert_eq!(Run.results.len(), FILE);
+			})
+		});
+	}
+
+	Group.finish();
+}
+
+criterion_group!(Benchmark, Compile, Batch);
+
+criterion_main!(Benchmark);
+
+use std::{hint::black_box, path::Path};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+use Library::{
+	Fn::SWC::Watch::Compile::Fn,
+	Struct::SWC::{Cache, Compiler, CompilerConfig, Option},
+};
@@ -0,0 +1,305 @@
+#![allow(non_snake_case)]
+
+/// Runs `RestSWC` in `Directory` with `Argument` and returns how it exited.
+fn Run(Directory:&Path, Argument:&[&str]) -> ExitStatus {
+	Command::new(env!("CARGO_BIN_EXE_RestSWC"))
+		.args(Argument)
+		.current_dir(Directory)
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.status()
+		.expect("Cannot RestSWC.")
+}
+
+/// Runs `RestSWC -` in `Directory`, writing `Input` to its standard input,
+/// and returns how it exited and what it printed.
+fn Pipe(Directory:&Path, Input:&str) -> (ExitStatus, String) {
+	let mut Child = Command::new(env!("CARGO_BIN_EXE_RestSWC"))
+		.arg("-")
+		.current_dir(Directory)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::null())
+		.spawn()
+		.expect("Cannot RestSWC.");
+
+	Child.stdin.take().unwrap().write_all(Input.as_bytes()).unwrap();
+
+	let Output = Child.wait_with_output().expect("Cannot wait.");
+
+	(Output.status, String::from_utf8_lossy(&Output.stdout).into_owned())
+}
+
+/// Runs `RestSWC` in `Directory` with `Argument` and returns how it exited and
+/// what it printed.
+fn Capture(Directory:&Path, Argument:&[&str]) -> (ExitStatus, String) {
+	let Output = Command::new(env!("CARGO_BIN_EXE_RestSWC"))
+		.args(Argument)
+		.current_dir(Directory)
+		.stderr(Stdio::null())
+		.output()
+		.expect("Cannot RestSWC.");
+
+	(Output.status, String::from_utf8_lossy(&Output.stdout).into_owned())
+}
+
+/// Writes `Content` to `File`, creating its directory, and sets its mtime to
+/// `Second` seconds into a fixed past day.
+fn Touch(File:&Path, Content:&str, Second:u64) {
+	fs::create_dir_all(File.parent().unwrap()).unwrap();
+
+	fs::write(File, Content).unwrap();
+
+	fs::File::options()
+		.write(true)
+		.open(File)
+		.unwrap()
+		.set_modified(UNIX_EPOCH + Duration::from_secs(1_700_000_000 + Second))
+		.unwrap();
+}
+
+#[test]
+fn OneShotSucceeds() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	fs::write(Directory.path().join("a.ts"), "export const a: number = 1;\n").unwrap();
+
+	let Status = Run(Directory.path(), &["."]);
+
+	assert_eq!(Status.code(), Some(0));
+
+	assert!(fs::read_to_string(Directory.path().join("a.js")).unwrap().contains("const a = 1;"));
+}
+
+#[test]
+fn OneShotFailsOnBrokenFile() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	fs::write(Directory.path().join("a.ts"), "export const a: number = 1;\n").unwrap();
+
+	fs::write(Directory.path().join("b.ts"), "const x = ;\n").unwrap();
+
+	let Status = Run(Directory.path(), &["."]);
+
+	assert_eq!(Status.code(), Some(1));
+
+	assert!(Directory.path().join("a.js").exists());
+
+	assert!(!Directory.path().join("b.js").exists());
+}
+
+#[test]
+fn NewerOutputIsKeptUntilTheConfigChanges() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	Touch(&Directory.path().join("a.ts"), "export const a: number = 1;\n", 0);
+
+	Touch(&Directory.path().join("a.js"), "stale", 10);
+
+	assert_eq!(Run(Directory.path(), &["."]).code(), Some(0));
+
+	assert_eq!(fs::read_to_string(Directory.path().join("a.js")).unwrap(), "stale");
+
+	Touch(&Directory.path().join("swc_config.json"), "{}", 20);
+
+	assert_eq!(Run(Directory.path(), &["."]).code(), Some(0));
+
+	assert!(fs::read_to_string(Directory.path().join("a.js")).unwrap().contains("const a = 1;"));
+}
+
+#[test]
+fn NewerOutputIsFoundThroughNestedConfig() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	let Package = Directory.path().join("pkg");
+
+	Touch(&Package.join("swc_config.json"), r#"{ "OutDir": "pkg/out", "Root": "./pkg" }"#, 0);
+
+	Touch(&Package.join("a.ts"), "export const a: number = 1;\n", 0);
+
+	Touch(&Package.join("out/a.js"), "stale", 10);
+
+	assert_eq!(Run(Directory.path(), &["."]).code(), Some(0));
+
+	assert_eq!(fs::read_to_string(Package.join("out/a.js")).unwrap(), "stale");
+
+	assert!(!Package.join("a.js").exists());
+}
+
+#[test]
+fn CachedSourceIgnoresNewerOutput() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	Touch(&Directory.path().join("a.ts"), "export const a: number = 1;\n", 0);
+
+	assert_eq!(Run(Directory.path(), &["."]).code(), Some(0));
+
+	Touch(&Directory.path().join("a.ts"), "export const a: number = 2;\n", 5);
+
+	Touch(&Directory.path().join("a.js"), "stale", 10);
+
+	assert_eq!(Run(Directory.path(), &["."]).code(), Some(0));
+
+	assert!(fs::read_to_string(Directory.path().join("a.js")).unwrap().contains("const a = 2;"));
+}
+
+#[test]
+fn RestignoreOfEntrySkipsGenerated() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	let Package = Directory.path().join("pkg");
+
+	Touch(&Package.join(".restignore"), "generated/\n", 0);
+
+	Touch(&Package.join("a.ts"), "export const a: number = 1;\n", 0);
+
+	Touch(&Package.join("generated/deep/b.ts"), "export const b: number = 1;\n", 0);
+
+	assert_eq!(Run(Directory.path(), &["pkg"]).code(), Some(0));
+
+	assert!(Package.join("a.js").exists());
+
+	assert!(!Package.join("generated/deep/b.js").exists());
+}
+
+#[test]
+fn CustomConfigIsApplied() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	fs::write(Directory.path().join("a.ts"), "export const a: number = 1;\n").unwrap();
+
+	fs::write(Directory.path().join("custom.json"), r#"{ "Module": "es6" }"#).unwrap();
+
+	assert_eq!(Run(Directory.path(), &["--Config", "custom.json", "."]).code(), Some(0));
+
+	assert!(fs::read_to_string(Directory.path().join("a.js"))
+		.unwrap()
+		.contains("export const a = 1;"));
+
+	fs::write(Directory.path().join("custom.json"), r#"{ "Target": "es1999" }"#).unwrap();
+
+	assert_eq!(Run(Directory.path(), &["--Config", "custom.json", "."]).code(), Some(1));
+}
+
+#[test]
+fn StdinCompilesToStdout() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	let (Status, Output) = Pipe(Directory.path(), "const x: number = 1;\n");
+
+	assert_eq!(Status.code(), Some(0));
+
+	assert!(Output.contains("const x = 1;"), "{}", Output);
+
+	assert_eq!(fs::read_dir(Directory.path()).unwrap().count(), 0);
+
+	assert_eq!(Pipe(Directory.path(), "const x = ;\n").0.code(), Some(1));
+}
+
+#[test]
+fn FilesFromCompilesOnlyTheList() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	for File in ["a.ts", "b.ts", "src/c.ts"] {
+		Touch(&Directory.path().join(File), "export const a: number = 1;\n", 0);
+	}
+
+	fs::write(Directory.path().join("list.txt"), "# changed\na.ts\n\n  src/c.ts  \n").unwrap();
+
+	assert_eq!(Run(Directory.path(), &["--FilesFrom", "list.txt"]).code(), Some(0));
+
+	assert!(Directory.path().join("a.js").exists());
+
+	assert!(Directory.path().join("src/c.js").exists());
+
+	assert!(!Directory.path().join("b.js").exists());
+}
+
+#[test]
+fn SingleFileCompilesAlone() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	fs::write(Directory.path().join("a.ts"), "export const a: number = 1;\n").unwrap();
+
+	fs::write(Directory.path().join("b.ts"), "export const b: number = 2;\n").unwrap();
+
+	assert_eq!(Run(Directory.path(), &["a.ts"]).code(), Some(0));
+
+	assert!(Directory.path().join("a.js").exists());
+
+	assert!(!Directory.path().join("b.js").exists());
+}
+
+#[test]
+fn ListPrintsWithoutCompiling() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	for File in ["a.ts", "src/b.tsx", "node_modules/c.ts", "d.js"] {
+		Touch(&Directory.path().join(File), "export const a = 1;\n", 0);
+	}
+
+	let (Status, Output) = Capture(Directory.path(), &["--List", "."]);
+
+	assert_eq!(Status.code(), Some(0));
+
+	let mut Listed = Output.lines().map(|Line| Path::new(Line).to_path_buf()).collect::<Vec<_>>();
+
+	Listed.sort();
+
+	assert_eq!(Listed, [Path::new("./a.ts"), Path::new("./src/b.tsx")], "{}", Output);
+
+	assert!(!Directory.path().join("a.js").exists());
+}
+
+#[test]
+fn CIReportIsNotOkWithABrokenFile() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	fs::write(Directory.path().join("a.ts"), "export const a: number = 1;\n").unwrap();
+
+	fs::write(Directory.path().join("b.ts"), "const x = ;\n").unwrap();
+
+	assert_eq!(Run(Directory.path(), &["--CIReport", "report.json", "."]).code(), Some(1));
+
+	let Report = serde_json::from_slice::<serde_json::Value>(
+		&fs::read(Directory.path().join("report.json")).unwrap(),
+	)
+	.unwrap();
+
+	assert_eq!(Report["ok"], false);
+
+	assert_eq!((Report["compiled"].as_u64(), Report["failed"].as_u64()), (Some(1), Some(1)));
+
+	assert_eq!(Report["files"].as_array().map(Vec::len), Some(2));
+}
+
+#[test]
+fn FailFastStillWritesTheCIReport() {
+	let Directory = tempfile::tempdir().expect("Cannot tempdir.");
+
+	for File in ["a.ts", "b.ts"] {
+		fs::write(Directory.path().join(File), "const x = ;\n").unwrap();
+	}
+
+	assert_eq!(
+		Run(Directory.path(), &["--FailFast", "--CIReport", "report.json", "."]).code(),
+		Some(1)
+	);
+
+	let Report = serde_json::from_slice::<serde_json::Value>(
+		&fs::read(Directory.path().join("report.json")).unwrap(),
+	)
+	.unwrap();
+
+	assert_eq!(Report["ok"], false);
+
+	assert_eq!(Report["failed"].as_u64(), Some(1));
+}
+
+use std::{
+	fs,
+	io::Write,
+	path::Path,
+	process::{Command, ExitStatus, Stdio},
+	time::{Duration, UNIX_EPOCH},
+};